@@ -0,0 +1,282 @@
+/// Options and subcommands of the command line.
+pub fn app() -> clap::App<'static, 'static> {
+    clap::App::new(env!("CARGO_PKG_NAME"))
+        .setting(clap::AppSettings::TrailingVarArg)
+        .setting(clap::AppSettings::AllowLeadingHyphen)
+        .version(env!("CARGO_PKG_VERSION"))
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .arg(
+            clap::Arg::with_name("time")
+                .short("t")
+                .long("time")
+                .takes_value(true)
+                .value_name("TIME")
+                .help("set time limit in seconds for a time mode game"),
+        )
+        .arg(
+            clap::Arg::with_name("zen")
+                .long("zen")
+                .conflicts_with_all(&["time", "command", "shell", "pid", "until-exists", "until-removed", "until-listening"])
+                .help("type freely with no time limit, until esc or ctrl+d"),
+        )
+        .arg(
+            clap::Arg::with_name("survival")
+                .long("survival")
+                .conflicts_with_all(&["time", "zen", "command", "shell", "pid", "until-exists", "until-removed", "until-listening", "code", "text", "top", "ranks", "min-length", "max-length"])
+                .help("start with 10 seconds, win time with correct words and lose it with mistakes as words get harder"),
+        )
+        .arg(
+            clap::Arg::with_name("players")
+                .long("players")
+                .takes_value(true)
+                .value_name("NAMES")
+                .conflicts_with_all(&["zen", "survival", "command", "shell", "pid", "until-exists", "until-removed", "until-listening"])
+                .help("take turns on the same words, e.g. alice,bob, and compare scores at the end"),
+        )
+        .arg(
+            clap::Arg::with_name("command")
+                .short("c")
+                .long("command")
+                .takes_value(true)
+                .value_name("COMMAND")
+                .help("a command to execute")
+                .multiple(true),
+        )
+        .arg(
+            clap::Arg::with_name("shell")
+                .short("s")
+                .long("shell")
+                .takes_value(true)
+                .value_name("COMMAND LINE")
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with("command")
+                .help(
+                    "a command line to run with $SHELL -c, repeat to run several one after another",
+                ),
+        )
+        .arg(
+            clap::Arg::with_name("pid")
+                .long("pid")
+                .takes_value(true)
+                .value_name("PID")
+                .conflicts_with_all(&["command", "shell"])
+                .help("play until the process with the given pid exits"),
+        )
+        .arg(
+            clap::Arg::with_name("until-exists")
+                .long("until-exists")
+                .takes_value(true)
+                .value_name("PATH")
+                .conflicts_with_all(&["command", "shell", "pid"])
+                .help("play until PATH appears, e.g. a build artifact"),
+        )
+        .arg(
+            clap::Arg::with_name("until-removed")
+                .long("until-removed")
+                .takes_value(true)
+                .value_name("PATH")
+                .conflicts_with_all(&["command", "shell", "pid", "until-exists"])
+                .help("play until PATH disappears, e.g. a lockfile"),
+        )
+        .arg(
+            clap::Arg::with_name("until-listening")
+                .long("until-listening")
+                .takes_value(true)
+                .value_name("PORT")
+                .conflicts_with_all(&["command", "shell", "pid", "until-exists", "until-removed"])
+                .help("play until a local TCP port starts listening"),
+        )
+        .arg(
+            clap::Arg::with_name("on-finish")
+                .long("on-finish")
+                .takes_value(true)
+                .value_name("ACTION")
+                .possible_values(&["stop", "word", "minute", "continue"])
+                .help("when the command or what is waited for is done: stop right away (default), let the current word or minute be finished, or continue for --time seconds"),
+        )
+        .arg(
+            clap::Arg::with_name("pager")
+                .long("pager")
+                .conflicts_with("output-file")
+                .help("show the command output in $PAGER when done"),
+        )
+        .arg(
+            clap::Arg::with_name("output-file")
+                .short("o")
+                .long("output-file")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("write the command output to FILE when done"),
+        )
+        .arg(
+            clap::Arg::with_name("tail")
+                .long("tail")
+                .takes_value(true)
+                .value_name("LINES")
+                .help("number of command output lines shown while it runs, up to 5 (default 3)"),
+        )
+        .arg(
+            clap::Arg::with_name("stderr")
+                .long("stderr")
+                .takes_value(true)
+                .value_name("HANDLING")
+                .possible_values(&["interleave", "separate", "discard"])
+                .help("show command stderr mixed with stdout (default), after it, or not at all"),
+        )
+        .arg(
+            clap::Arg::with_name("pty")
+                .long("pty")
+                .help("run the command in a pseudo-terminal, keeping its colors and prompts"),
+        )
+        .arg(
+            clap::Arg::with_name("caret")
+                .long("caret")
+                .takes_value(true)
+                .value_name("STYLE")
+                .possible_values(&["block", "underline", "bar", "none"])
+                .help("set caret style"),
+        )
+        .arg(
+            clap::Arg::with_name("language")
+                .short("l")
+                .long("language")
+                .takes_value(true)
+                .value_name("LANGUAGE")
+                .help("set word list language"),
+        )
+        .arg(
+            clap::Arg::with_name("list-languages")
+                .long("list-languages")
+                .help("list available languages"),
+        )
+        .arg(
+            clap::Arg::with_name("top")
+                .long("top")
+                .takes_value(true)
+                .value_name("N")
+                .conflicts_with("ranks")
+                .help("practice only the N most frequent words, e.g. 200 or 1000"),
+        )
+        .arg(
+            clap::Arg::with_name("ranks")
+                .long("ranks")
+                .takes_value(true)
+                .value_name("FROM-TO")
+                .help("practice words within a frequency rank range, e.g. 1000-2000"),
+        )
+        .arg(
+            clap::Arg::with_name("min-length")
+                .long("min-length")
+                .takes_value(true)
+                .value_name("CHARS")
+                .help("skip words shorter than CHARS"),
+        )
+        .arg(
+            clap::Arg::with_name("max-length")
+                .long("max-length")
+                .takes_value(true)
+                .value_name("CHARS")
+                .help("skip words longer than CHARS"),
+        )
+        .arg(
+            clap::Arg::with_name("punctuation")
+                .short("p")
+                .long("punctuation")
+                .help("add capitalization and punctuation to words"),
+        )
+        .arg(
+            clap::Arg::with_name("numbers")
+                .short("n")
+                .long("numbers")
+                .help("mix random numbers into words"),
+        )
+        .arg(
+            clap::Arg::with_name("code")
+                .long("code")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("type snippets of source code from a file or directory"),
+        )
+        .arg(
+            clap::Arg::with_name("text")
+                .long("text")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with("code")
+                .help("type a passage from a file verbatim, line breaks included"),
+        )
+        .arg(
+            clap::Arg::with_name("pace")
+                .long("pace")
+                .takes_value(true)
+                .value_name("WPM")
+                .help("race a ghost caret moving at WPM, or at the pace of your best time mode game of the same length with 'best'"),
+        )
+        .arg(
+            clap::Arg::with_name("highlight")
+                .long("highlight")
+                .help("highlight the next expected character"),
+        )
+        .arg(
+            clap::Arg::with_name("leaderboard-file")
+                .long("leaderboard-file")
+                .takes_value(true)
+                .value_name("FILE")
+                .env("FTYPER_LEADERBOARD")
+                .global(true)
+                .help("leaderboard to record timed games in, e.g. on a shared mount (default ~/.local/share/ftyper/leaderboard)"),
+        )
+        .arg(
+            clap::Arg::with_name("name")
+                .long("name")
+                .takes_value(true)
+                .value_name("NAME")
+                .global(true)
+                .help("name shown to the other players and on the leaderboard (default $USER)"),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("daily")
+                .about("take the challenge of the day: a 60 second game on words that are the same for everyone, counted once a day"),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("leaderboard")
+                .about("show the fastest players on the leaderboard")
+                .arg(
+                    clap::Arg::with_name("mode")
+                        .long("mode")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .help("games to rank, e.g. time30 for 30 second games, or time60-top200-punctuation for games with word options (default time60)"),
+                )
+                .arg(
+                    clap::Arg::with_name("limit")
+                        .long("limit")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("number of players shown (default 10)"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("host")
+                .about("host a race for others to join over the network, on the words picked by the options given before 'host'")
+                .arg(
+                    clap::Arg::with_name("port")
+                        .long("port")
+                        .takes_value(true)
+                        .value_name("PORT")
+                        .help("port to listen on (default 7878)"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("join")
+                .about("join a race hosted with 'ftyper host'")
+                .arg(
+                    clap::Arg::with_name("address")
+                        .required(true)
+                        .value_name("ADDRESS")
+                        .help("address of the host, e.g. 192.168.1.20 or 192.168.1.20:7878"),
+                ),
+        )
+}
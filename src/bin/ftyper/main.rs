@@ -0,0 +1,277 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+
+use ftyper::code::Sources;
+use ftyper::config::{Caret, Config};
+use ftyper::game::{CommandMode, Game, Task};
+use ftyper::language;
+use ftyper::leaderboard::{self, Leaderboard};
+use ftyper::mode::{OnFinish, SurvivalMode, TimeMode, WatchMode, ZenMode};
+use ftyper::output::{self, Dump, Stderr};
+use ftyper::replay::{self, Pace, Replay};
+use ftyper::types::Result;
+use ftyper::watch::{self, Watch};
+use ftyper::words::{Difficulty, WordFeed};
+
+mod cli;
+mod multiplayer;
+mod records;
+
+fn main() -> Result<()> {
+    let matches = cli::app().get_matches();
+
+    if matches.is_present("list-languages") {
+        for language in language::LANGUAGES {
+            println!("{}", language.name);
+        }
+        return Ok(());
+    }
+
+    let leaderboard = match matches.value_of("leaderboard-file") {
+        Some(path) => Some(Leaderboard::new(Path::new(path))),
+        None => Leaderboard::default_path().map(|path| Leaderboard::new(&path)),
+    };
+    if let Some(matches) = matches.subcommand_matches("leaderboard") {
+        return records::show_leaderboard(matches, &leaderboard);
+    }
+
+    let mut config = Config::default();
+    if let Some(name) = matches.value_of("language") {
+        config.language = match language::find(name) {
+            Some(language) => language,
+            None => {
+                println!(
+                    "error: language '{}' is not available, see --list-languages",
+                    name
+                );
+                return Ok(());
+            }
+        };
+    }
+
+    let mut difficulty = Difficulty::default();
+    if let Some(top) = matches.value_of("top") {
+        difficulty.ranks = 0..usize::from_str(top)?;
+    }
+    if let Some(ranks) = matches.value_of("ranks") {
+        difficulty.ranks = parse_ranks(ranks)?;
+    }
+    if let Some(min) = matches.value_of("min-length") {
+        difficulty.lengths = usize::from_str(min)?..=*difficulty.lengths.end();
+    }
+    if let Some(max) = matches.value_of("max-length") {
+        difficulty.lengths = *difficulty.lengths.start()..=usize::from_str(max)?;
+    }
+    if WordFeed::new(config.language.words, &difficulty).is_empty() {
+        println!("error: no words match the selected ranks and lengths");
+        return Ok(());
+    }
+    config.difficulty = difficulty;
+
+    if let Some(caret) = matches.value_of("caret") {
+        config.caret = Caret::from_str(caret)?;
+    }
+    config.highlight = matches.is_present("highlight");
+    config.punctuation = matches.is_present("punctuation");
+    config.numbers = matches.is_present("numbers");
+    if let Some(path) = matches.value_of("code") {
+        match Sources::load(Path::new(path)) {
+            Ok(sources) => config.code = Some(sources),
+            Err(err) => {
+                println!("error: {}", err);
+                return Ok(());
+            }
+        }
+    }
+    if let Some(path) = matches.value_of("text") {
+        match fs::read_to_string(path) {
+            Ok(text) if !text.trim().is_empty() => config.text = Some(text),
+            Ok(_) => {
+                println!("error: {} is empty", path);
+                return Ok(());
+            }
+            Err(err) => {
+                println!("error: {}", err);
+                return Ok(());
+            }
+        }
+    }
+
+    let time = FromStr::from_str(matches.value_of("time").unwrap_or("60")).unwrap_or(60);
+    // Personal bests are kept per ranked mode, so a ghost races the same words.
+    let ranked_mode = leaderboard::mode(time, &config);
+    let best_path = ranked_mode.as_deref().and_then(replay::best_path);
+    if let Some(pace) = matches.value_of("pace") {
+        config.pace = if pace == "best" {
+            match best_path.as_deref().map(Replay::load) {
+                Some(Ok(replay)) => Some(Pace::Replay(replay)),
+                _ if ranked_mode.is_none() => {
+                    println!("error: no personal bests are kept for --code or --text games");
+                    return Ok(());
+                }
+                _ => {
+                    println!(
+                        "error: no personal best for {}s games on these words yet",
+                        time
+                    );
+                    return Ok(());
+                }
+            }
+        } else {
+            Some(Pace::Wpm(f32::from_str(pace)?))
+        };
+    }
+
+    let on_finish = match matches.value_of("on-finish") {
+        Some("word") => OnFinish::Word,
+        Some("minute") => OnFinish::Minute,
+        Some("continue") => OnFinish::Continue(time),
+        _ => OnFinish::Stop,
+    };
+
+    let mut command_lines: Vec<Vec<String>> = Vec::new();
+    if let Some(values) = matches.values_of("command") {
+        command_lines.push(values.map(String::from).collect());
+    }
+    if let Some(values) = matches.values_of("shell") {
+        let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        for line in values {
+            command_lines.push(vec![shell.clone(), "-c".to_string(), line.to_string()]);
+        }
+    }
+
+    let watch: Option<io::Result<Box<dyn Watch>>> = if let Some(pid) = matches.value_of("pid") {
+        Some(watch::Process::attach(u32::from_str(pid)?).map(|w| Box::new(w) as _))
+    } else if let Some(path) = matches.value_of("until-exists") {
+        Some(watch::Appears::new(Path::new(path)).map(|w| Box::new(w) as _))
+    } else if let Some(path) = matches.value_of("until-removed") {
+        Some(watch::Disappears::new(Path::new(path)).map(|w| Box::new(w) as _))
+    } else if let Some(port) = matches.value_of("until-listening") {
+        Some(watch::Listening::new(u16::from_str(port)?).map(|w| Box::new(w) as _))
+    } else {
+        None
+    };
+
+    if let Some(matches) = matches.subcommand_matches("host") {
+        multiplayer::host(matches, config, time, &leaderboard)?;
+    } else if let Some(matches) = matches.subcommand_matches("daily") {
+        records::daily(matches, config, &leaderboard)?;
+    } else if let Some(matches) = matches.subcommand_matches("join") {
+        multiplayer::join(matches, config, &leaderboard)?;
+    } else if matches.is_present("survival") {
+        let mut game = Game::new(SurvivalMode::new(), config);
+        if let Err(err) = game.start() {
+            println!("error: {}", err);
+        }
+    } else if matches.is_present("zen") {
+        let mut game = Game::new(ZenMode::new(), config);
+        if let Err(err) = game.start() {
+            println!("error: {}", err);
+        }
+    } else if let Some(watch) = watch {
+        let watch = match watch {
+            Ok(watch) => watch,
+            Err(err) => {
+                println!("error: {}", err);
+                return Ok(());
+            }
+        };
+        let mode = WatchMode::new(watch, on_finish);
+        let mut game = Game::new(mode, config);
+        if let Err(err) = game.start() {
+            println!("error: {}", err);
+        }
+    } else if !command_lines.is_empty() {
+        let tail = FromStr::from_str(matches.value_of("tail").unwrap_or("3")).unwrap_or(3);
+        let stderr = Stderr::from_str(matches.value_of("stderr").unwrap_or("interleave"))?;
+        let dump = if let Some(path) = matches.value_of("output-file") {
+            Dump::File(path.into())
+        } else if matches.is_present("pager") {
+            Dump::Pager
+        } else {
+            Dump::Print
+        };
+
+        let tasks = command_lines
+            .iter()
+            .map(|command_args| {
+                let program = command_args.first().unwrap();
+                let rest = &command_args[1..];
+
+                let mut command = process::Command::new(program);
+
+                command
+                    .args(rest)
+                    .stdin(process::Stdio::null())
+                    .stdout(process::Stdio::piped())
+                    .stderr(if stderr == Stderr::Discard {
+                        process::Stdio::null()
+                    } else {
+                        process::Stdio::piped()
+                    });
+
+                let name = match command_args.get(1).map(String::as_str) {
+                    Some("-c") if command_args.len() == 3 => command_args[2].clone(),
+                    _ => command_args.join(" "),
+                };
+                Task { name, command }
+            })
+            .collect();
+
+        let mut mode = CommandMode::new(tasks, on_finish);
+        mode.set_tail(tail);
+        mode.set_stderr(stderr);
+        mode.set_pty(matches.is_present("pty"));
+        mode.set_dump(dump);
+        let mut game = Game::new(mode, config);
+        match game.start() {
+            Ok(Some(status)) => process::exit(output::exit_code(status)),
+            Ok(None) => process::exit(output::NOT_STARTED),
+            Err(err) => {
+                println!("error: {}", err);
+                process::exit(output::NOT_STARTED);
+            }
+        }
+    } else if let Some(names) = matches.value_of("players") {
+        multiplayer::hot_seat(names, config, time, &leaderboard)?;
+    } else {
+        let mut game = Game::new(TimeMode { time }, config);
+        if let Err(err) = game.start() {
+            println!("error: {}", err);
+        }
+
+        let replay = game.replay();
+        let finished = game.score().time >= time;
+        if let Some(mode) = ranked_mode.filter(|_| finished) {
+            let name = records::player_name(&matches)?;
+            records::record(&leaderboard, &name, &mode, game.score());
+        }
+        if let Some(path) = best_path.filter(|_| finished) {
+            let best = Replay::load(&path).map_or(0.0, |best| best.wpm);
+            if replay.wpm > best {
+                match replay.save(&path) {
+                    Ok(()) => println!("New personal best: {:.0}wpm", replay.wpm),
+                    Err(err) => println!("error: failed to save {}: {}", path.display(), err),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a 1-based, inclusive `FROM-TO` rank range.
+fn parse_ranks(value: &str) -> Result<Range<usize>> {
+    let mut bounds = value.splitn(2, '-');
+    let from = usize::from_str(bounds.next().unwrap_or("").trim())?;
+    let to = usize::from_str(bounds.next().unwrap_or("").trim())?;
+    if from == 0 || from > to {
+        return Err(format!("invalid rank range: {}", value).into());
+    }
+    Ok(from - 1..to)
+}
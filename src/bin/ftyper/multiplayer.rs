@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use ftyper::config::Config;
+use ftyper::game::Game;
+use ftyper::leaderboard::{self, Leaderboard};
+use ftyper::mode::{Key, TimeMode};
+use ftyper::net::{self, Lobby, Peer, Setup};
+use ftyper::race::mode::RaceMode;
+use ftyper::race::Standings;
+use ftyper::terminal::{Input, Stdin, Stdout};
+use ftyper::timer::SystemClock;
+use ftyper::types::Result;
+
+use crate::records::{player_name, record};
+
+/// Gathers players over the network and races them on `config`'s words.
+pub fn host(
+    matches: &clap::ArgMatches,
+    config: Config,
+    time: u32,
+    leaderboard: &Option<Leaderboard>,
+) -> Result<()> {
+    if config.code.is_some() || config.text.is_some() {
+        println!("error: races are played on words, without --code or --text");
+        return Ok(());
+    }
+    let name = player_name(matches)?;
+    let port = match matches.value_of("port") {
+        Some(port) => u16::from_str(port)?,
+        None => net::DEFAULT_PORT,
+    };
+    let mut lobby = match Lobby::open(port, &name) {
+        Ok(lobby) => lobby,
+        Err(err) => {
+            println!("error: can't listen on port {}: {}", port, err);
+            return Ok(());
+        }
+    };
+    println!(
+        "Hosting a race on port {}. Press enter once everyone has joined.",
+        lobby.port()?
+    );
+    let input = Rc::new(RefCell::new(Stdin::new()));
+    loop {
+        for player in lobby.accept()? {
+            println!("{} joined", player);
+        }
+        if input.borrow_mut().next_key() == Some(Key::Char('\n')) {
+            if lobby.players().len() > 1 {
+                break;
+            }
+            println!("Nobody has joined yet");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let setup = Setup {
+        seed: rand::random(),
+        time,
+        language: config.language.name.to_string(),
+        difficulty: config.difficulty.clone(),
+        punctuation: config.punctuation,
+        numbers: config.numbers,
+        players: Vec::new(),
+    };
+    let (peer, setup) = lobby.start(setup);
+    race(peer, &setup, config, Box::new(input), leaderboard);
+    Ok(())
+}
+
+/// Joins a race hosted with `ftyper host` once it starts.
+pub fn join(
+    matches: &clap::ArgMatches,
+    mut config: Config,
+    leaderboard: &Option<Leaderboard>,
+) -> Result<()> {
+    let name = player_name(matches)?;
+    let address = matches.value_of("address").unwrap();
+    println!("Waiting for the host to start the race...");
+    let (peer, setup) = match net::join(address, &name) {
+        Ok(joined) => joined,
+        Err(err) => {
+            println!("error: {}", err);
+            return Ok(());
+        }
+    };
+    setup.configure(&mut config)?;
+    race(peer, &setup, config, Box::new(Stdin::new()), leaderboard);
+    Ok(())
+}
+
+/// Lets the players of a comma separated list take turns on the same words.
+pub fn hot_seat(
+    names: &str,
+    mut config: Config,
+    time: u32,
+    leaderboard: &Option<Leaderboard>,
+) -> Result<()> {
+    let names: Vec<&str> = names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.len() < 2 {
+        println!("error: a race needs at least two players");
+        return Ok(());
+    }
+    config.seed = Some(rand::random());
+    let ranked_mode = leaderboard::mode(time, &config);
+
+    let input = Rc::new(RefCell::new(Stdin::new()));
+    let mut standings = Standings::new();
+    for name in names {
+        println!("{}'s turn, press enter when ready", name);
+        wait_for_enter(&mut *input.borrow_mut());
+        let mut game = Game::with_terminal(
+            TimeMode { time },
+            config.clone(),
+            Box::new(input.clone()),
+            Box::new(Stdout::new()),
+            Rc::new(SystemClock::new()),
+        );
+        if let Err(err) = game.start() {
+            println!("error: {}", err);
+            return Ok(());
+        }
+        let score = game.score();
+        if let Some(mode) = ranked_mode.as_deref().filter(|_| score.time >= time) {
+            record(leaderboard, name, mode, score);
+        }
+        standings.add(name, score);
+    }
+    print!("{}", standings.table());
+    Ok(())
+}
+
+/// Plays a race over the network and shows how everyone did.
+fn race(
+    peer: Peer,
+    setup: &Setup,
+    config: Config,
+    input: Box<dyn Input>,
+    leaderboard: &Option<Leaderboard>,
+) {
+    let name = peer.name().to_string();
+    let ranked_mode = leaderboard::mode(setup.time, &config);
+    let mut game = Game::with_terminal(
+        RaceMode::new(peer, setup),
+        config,
+        input,
+        Box::new(Stdout::new()),
+        Rc::new(SystemClock::new()),
+    );
+    match game.start() {
+        Ok(standings) => print!("{}", standings.table()),
+        Err(err) => println!("error: {}", err),
+    }
+    let score = game.score();
+    if let Some(mode) = ranked_mode.filter(|_| score.time >= setup.time) {
+        record(leaderboard, &name, &mode, score);
+    }
+}
+
+/// Waits for the next player to press enter, ignoring keys typed before.
+fn wait_for_enter(input: &mut impl Input) {
+    while input.next_key().is_some() {}
+    while input.next_key() != Some(Key::Char('\n')) {
+        thread::sleep(Duration::from_millis(50));
+    }
+}
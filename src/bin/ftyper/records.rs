@@ -0,0 +1,108 @@
+use std::env;
+use std::str::FromStr;
+
+use ftyper::config::Config;
+use ftyper::daily::{self, Day, History};
+use ftyper::game::Game;
+use ftyper::leaderboard::{Entry, Leaderboard};
+use ftyper::mode::TimeMode;
+use ftyper::race::Standings;
+use ftyper::score::Score;
+use ftyper::types::Result;
+
+/// Shows the fastest players of the mode asked for.
+pub fn show_leaderboard(
+    matches: &clap::ArgMatches,
+    leaderboard: &Option<Leaderboard>,
+) -> Result<()> {
+    let mode = matches.value_of("mode").unwrap_or("time60");
+    let limit = usize::from_str(matches.value_of("limit").unwrap_or("10"))?;
+    let top = match leaderboard {
+        Some(leaderboard) => leaderboard.top(mode, limit)?,
+        None => Standings::new(),
+    };
+    if top.is_empty() {
+        println!("No {} games on the leaderboard yet", mode);
+    } else {
+        print!("{}", top.table());
+    }
+    Ok(())
+}
+
+/// Plays the challenge of the day, keeping the first attempt and the streak.
+pub fn daily(
+    matches: &clap::ArgMatches,
+    config: Config,
+    leaderboard: &Option<Leaderboard>,
+) -> Result<()> {
+    let history = match History::default_path() {
+        Some(path) => History::new(&path),
+        None => {
+            println!("error: no place to keep the daily history, $HOME is not set");
+            return Ok(());
+        }
+    };
+    let today = Day::today();
+    // Only the seed picks the words, so word options are left out.
+    let config = Config {
+        caret: config.caret,
+        highlight: config.highlight,
+        seed: Some(today.seed()),
+        ..Config::default()
+    };
+    let mut game = Game::new(TimeMode { time: daily::TIME }, config);
+    if let Err(err) = game.start() {
+        println!("error: {}", err);
+        return Ok(());
+    }
+
+    // The words were shown, so even quitting before the first key uses up the
+    // day's attempt.
+    let score = game.score();
+    if history.record(today, &score)? {
+        if score.time >= daily::TIME {
+            let name = player_name(matches)?;
+            record(leaderboard, &name, &format!("daily-{}", today), score);
+            println!("Challenge of {} taken!", today);
+        } else {
+            println!("Challenge of {} given up after {}s.", today, score.time);
+        }
+    } else if let Some(official) = history.attempt(today)? {
+        println!(
+            "You already took today's challenge at {:.0}wpm, this one was practice.",
+            official.wpm
+        );
+    }
+    let streak = history.streak(today)?;
+    println!(
+        "Streak: {} day{}",
+        streak,
+        if streak == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+/// Adds a game played to the end to the leaderboard.
+pub fn record(leaderboard: &Option<Leaderboard>, name: &str, mode: &str, score: Score) {
+    if let Some(leaderboard) = leaderboard {
+        if let Err(err) = leaderboard.record(&Entry::new(name, mode, score)) {
+            println!(
+                "error: failed to record the game in {}: {}",
+                leaderboard.path().display(),
+                err
+            );
+        }
+    }
+}
+
+/// The name given with `--name`, or the login name.
+pub fn player_name(matches: &clap::ArgMatches) -> Result<String> {
+    let name = match matches.value_of("name") {
+        Some(name) => name.to_string(),
+        None => env::var("USER").unwrap_or_else(|_| "player".to_string()),
+    };
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(format!("invalid player name '{}', names can't contain spaces", name).into());
+    }
+    Ok(name)
+}
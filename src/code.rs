@@ -0,0 +1,199 @@
+use crate::words::{split_rows, tokenize, Feed, LiveWord, TAB_WIDTH};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const SOURCE_EXTENSIONS: [&str; 24] = [
+    "c", "cc", "cpp", "cs", "go", "h", "hpp", "hs", "java", "js", "jsx", "kt", "lua", "ml", "php",
+    "py", "rb", "rs", "scala", "sh", "swift", "ts", "tsx", "zig",
+];
+const SKIPPED_DIRS: [&str; 5] = ["build", "dist", "node_modules", "target", "vendor"];
+const CLOSING_TOKENS: [&str; 5] = ["}", ")", "]", "end", "fi"];
+const MAX_SNIPPET_LINES: usize = 12;
+
+/// Source files to draw snippets from, kept as lines with trailing whitespace removed.
+#[derive(Debug, Clone)]
+pub struct Sources {
+    files: Vec<Vec<String>>,
+}
+
+impl Sources {
+    /// Reads a single file, or every source file found under a directory.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut files = Vec::new();
+        if path.is_dir() {
+            collect_dir(path, &mut files)?;
+        } else {
+            files.push(read_lines(path)?);
+        }
+        files.retain(|lines| lines.iter().any(|line| !line.is_empty()));
+
+        if files.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no source code found in {}", path.display()),
+            ));
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Picks a random block of code: a line that opens a more indented block,
+    /// followed by the block body and its closing line.
+    fn snippet(&self, rng: &mut ChaCha8Rng) -> Vec<&str> {
+        let lines = &self.files[rng.gen_range(0..self.files.len())];
+        let openers: Vec<usize> = (0..lines.len())
+            .filter(|&i| {
+                !lines[i].is_empty()
+                    && next_non_empty(lines, i + 1)
+                        .is_some_and(|next| indent_width(next) > indent_width(&lines[i]))
+            })
+            .collect();
+        let start = if openers.is_empty() {
+            let non_empty: Vec<usize> =
+                (0..lines.len()).filter(|&i| !lines[i].is_empty()).collect();
+            non_empty[rng.gen_range(0..non_empty.len())]
+        } else {
+            openers[rng.gen_range(0..openers.len())]
+        };
+
+        let base = indent_width(&lines[start]);
+        let mut snippet = vec![lines[start].as_str()];
+        for line in lines[start + 1..].iter() {
+            if snippet.len() == MAX_SNIPPET_LINES {
+                break;
+            }
+            if line.is_empty() || indent_width(line) > base {
+                snippet.push(line);
+                continue;
+            }
+            let first = line.split_whitespace().next().unwrap_or("");
+            if indent_width(line) == base && CLOSING_TOKENS.iter().any(|t| first.starts_with(t)) {
+                snippet.push(line);
+            }
+            break;
+        }
+        while snippet.last().is_some_and(|line| line.is_empty()) {
+            snippet.pop();
+        }
+
+        snippet
+    }
+}
+
+/// Feeds the `WordQueue` one source line per row, wrapping lines too wide for a
+/// row, with indentation typed as tabs and line breaks, blank lines included,
+/// typed with Enter.
+pub struct CodeFeed {
+    sources: Sources,
+    rows: VecDeque<Vec<LiveWord>>,
+    rng: ChaCha8Rng,
+}
+
+impl CodeFeed {
+    pub fn new(sources: Sources) -> Self {
+        Self {
+            sources,
+            rows: VecDeque::new(),
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    /// Makes the snippets picked the same for every feed with the same seed and
+    /// sources.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    fn load_snippet(&mut self, fit_row_into_len: u8) {
+        let snippet = self.sources.snippet(&mut self.rng);
+        let base = snippet
+            .iter()
+            .filter(|line| !line.is_empty())
+            .map(|line| indent_width(line))
+            .min()
+            .unwrap_or(0);
+        let unit = snippet
+            .iter()
+            .map(|line| indent_width(line).saturating_sub(base))
+            .filter(|&width| width > 0)
+            .min()
+            .unwrap_or(TAB_WIDTH);
+
+        let text: String = snippet
+            .iter()
+            .map(|line| {
+                let levels = indent_width(line).saturating_sub(base).div_ceil(unit);
+                format!("{}{}\n", "\t".repeat(levels), line.trim_start())
+            })
+            .collect();
+        self.rows
+            .extend(split_rows(tokenize(&text), fit_row_into_len as usize));
+    }
+}
+
+impl Feed for CodeFeed {
+    fn next_row(&mut self, fit_row_into_len: u8) -> Vec<LiveWord> {
+        if self.rows.is_empty() {
+            self.load_snippet(fit_row_into_len);
+        }
+        self.rows.pop_front().unwrap()
+    }
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<Vec<String>>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|entry| entry.path());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_dir(&path, files)?;
+            }
+        } else if is_source_file(&path) {
+            // Binary or non UTF-8 files aren't worth aborting for.
+            if let Ok(lines) = read_lines(&path) {
+                files.push(lines);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| SOURCE_EXTENSIONS.contains(&extension))
+}
+
+fn read_lines(path: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect())
+}
+
+fn next_non_empty(lines: &[String], from: usize) -> Option<&str> {
+    lines
+        .iter()
+        .skip(from)
+        .find(|line| !line.is_empty())
+        .map(|line| line.as_str())
+}
+
+/// Width of the leading whitespace, counting a tab as `TAB_WIDTH` columns.
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
@@ -1,6 +1,16 @@
 use crate::layout::{Layout as ILayout, Print, State, Template, Transform};
+use crate::mode::MAX_PROGRESS_LINES;
 use std::collections::HashMap;
 
+const PROGRESS_TEMPLATE: [&str; MAX_PROGRESS_LINES + 2] = [
+    "{{line1}}",
+    "{{line2}}",
+    "{{line3}}",
+    "{{line4}}",
+    "{{line5}}",
+    "{{status}}",
+    "",
+];
 const WORDS_TEMPLATE: [&str; 3] = ["", "{{row1}}", "{{row2}}"];
 const CURRENT_WORD_TEMPLATE: [&str; 3] = ["", "{{word}}", ""];
 const SCORE_TEMPLATE: [&str; 3] = [
@@ -8,24 +18,50 @@ const SCORE_TEMPLATE: [&str; 3] = [
     "time: {{time}}s   correct: {{correct}}  incorrect: {{incorrect}}  accuracy: {{accuracy}}%  speed: {{wpm}}wpm",
     ""
 ];
+const STROKES_TEMPLATE: [&str; 2] = [
+    "letters accuracy: {{letters}}%  symbols accuracy: {{symbols}}%",
+    "",
+];
 
 #[derive(Debug)]
 pub enum Component {
-    Words { state: HashMap<String, String> },
-    Word { state: HashMap<String, String> },
-    Score { state: HashMap<String, String> },
+    Words {
+        state: HashMap<String, String>,
+    },
+    Word {
+        state: HashMap<String, String>,
+    },
+    Score {
+        state: HashMap<String, String>,
+    },
+    Strokes {
+        state: HashMap<String, String>,
+    },
+    Progress {
+        state: HashMap<String, String>,
+        lines: usize,
+    },
 }
 
 impl Component {
     pub fn new(id: &str) -> Self {
         let state = HashMap::new();
-        match &id {
-            &"words" => Component::Words { state },
-            &"word" => Component::Word { state },
-            &"score" => Component::Score { state },
+        match id {
+            "words" => Component::Words { state },
+            "word" => Component::Word { state },
+            "score" => Component::Score { state },
+            "strokes" => Component::Strokes { state },
             _ => Component::Word { state },
         }
     }
+
+    /// Command output panel showing the last `lines` lines above a status line.
+    pub fn progress(lines: usize) -> Self {
+        Component::Progress {
+            state: HashMap::new(),
+            lines: lines.min(MAX_PROGRESS_LINES),
+        }
+    }
 }
 
 impl Transform for Component {}
@@ -38,6 +74,8 @@ impl Template for Component {
             Component::Words { .. } => "words",
             Component::Word { .. } => "word",
             Component::Score { .. } => "score",
+            Component::Strokes { .. } => "strokes",
+            Component::Progress { .. } => "progress",
         }
     }
     fn template(&self) -> &[&str] {
@@ -45,6 +83,8 @@ impl Template for Component {
             Component::Words { .. } => &WORDS_TEMPLATE,
             Component::Word { .. } => &CURRENT_WORD_TEMPLATE,
             Component::Score { .. } => &SCORE_TEMPLATE,
+            Component::Strokes { .. } => &STROKES_TEMPLATE,
+            Component::Progress { lines, .. } => &PROGRESS_TEMPLATE[MAX_PROGRESS_LINES - lines..],
         }
     }
 }
@@ -55,6 +95,8 @@ impl State for Component {
             Component::Words { state, .. } => state.clone(),
             Component::Word { state, .. } => state.clone(),
             Component::Score { state, .. } => state.clone(),
+            Component::Strokes { state, .. } => state.clone(),
+            Component::Progress { state, .. } => state.clone(),
         }
     }
     fn set_state(&mut self, new_state: &HashMap<String, String>) {
@@ -68,6 +110,12 @@ impl State for Component {
             Component::Score { state, .. } => {
                 *state = new_state.clone();
             }
+            Component::Strokes { state, .. } => {
+                *state = new_state.clone();
+            }
+            Component::Progress { state, .. } => {
+                *state = new_state.clone();
+            }
         }
     }
 }
@@ -0,0 +1,71 @@
+use crate::code::Sources;
+use crate::language::{self, Language};
+use crate::replay::Pace;
+use crate::words::Difficulty;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Caret {
+    Block,
+    Underline,
+    Bar,
+    None,
+}
+
+impl FromStr for Caret {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(Caret::Block),
+            "underline" => Ok(Caret::Underline),
+            "bar" => Ok(Caret::Bar),
+            "none" => Ok(Caret::None),
+            _ => Err(format!("unknown caret style: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub caret: Caret,
+    pub highlight: bool,
+    pub language: Language,
+    pub difficulty: Difficulty,
+    pub punctuation: bool,
+    pub numbers: bool,
+    pub code: Option<Sources>,
+    pub text: Option<String>,
+    pub pace: Option<Pace>,
+    /// Picks the same words on every game played with it.
+    pub seed: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            caret: Caret::Block,
+            highlight: false,
+            language: language::ENGLISH,
+            difficulty: Difficulty::default(),
+            punctuation: false,
+            numbers: false,
+            code: None,
+            text: None,
+            pace: None,
+            seed: None,
+        }
+    }
+}
+
+/// Where scores and replays are kept: `$XDG_DATA_HOME/ftyper`, falling back to
+/// `~/.local/share/ftyper`.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("ftyper"))
+}
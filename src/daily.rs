@@ -0,0 +1,190 @@
+use crate::config;
+use crate::score::Score;
+use crate::words::StrokeCount;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds of the daily challenge.
+pub const TIME: u32 = 60;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A day counted from the Unix epoch in UTC, so that it changes at the same
+/// time for everyone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day(pub u64);
+
+impl Day {
+    pub fn today() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Day(now / SECONDS_PER_DAY)
+    }
+
+    /// Picks the words of the day's challenge.
+    pub fn seed(self) -> u64 {
+        self.0
+    }
+
+    pub fn previous(self) -> Self {
+        Day(self.0.saturating_sub(1))
+    }
+}
+
+/// Formats the day as a `YYYY-MM-DD` date.
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Counted from March 1st of year 0, in 400 year eras, so that leap days
+        // come last in a year.
+        let days = self.0 as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl FromStr for Day {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date: {}", s);
+        let fields: Vec<i64> = s
+            .splitn(3, '-')
+            .map(|field| i64::from_str(field).map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let (year, month, day) = match fields[..] {
+            [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+                (year, month, day)
+            }
+            _ => return Err(invalid()),
+        };
+
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+        if days < 0 {
+            return Err(invalid());
+        }
+        let parsed = Day(days as u64);
+        // Days past the end of the month, like February 30th, come out as another date.
+        if parsed.to_string() != s {
+            return Err(invalid());
+        }
+        Ok(parsed)
+    }
+}
+
+/// The official attempt of each day the challenge was taken, one per line. It is
+/// the first game opened that day, even if given up before the end.
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// `daily` in the data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        config::data_dir().map(|dir| dir.join("daily"))
+    }
+
+    /// Every attempt recorded, skipping the lines that can't be read.
+    pub fn attempts(&self) -> io::Result<Vec<(Day, Score)>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        Ok(text.lines().filter_map(parse_attempt).collect())
+    }
+
+    /// The official attempt of `day`, if the challenge was taken.
+    pub fn attempt(&self, day: Day) -> io::Result<Option<Score>> {
+        Ok(self
+            .attempts()?
+            .into_iter()
+            .find(|(attempt_day, _)| *attempt_day == day)
+            .map(|(_, score)| score))
+    }
+
+    /// Records the official attempt of `day`, unless there already is one.
+    /// Returns whether it was recorded.
+    pub fn record(&self, day: Day, score: &Score) -> io::Result<bool> {
+        if self.attempt(day)?.is_some() {
+            return Ok(false);
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            day, score.wpm, score.accuracy, score.correct, score.incorrect, score.time
+        )?;
+        Ok(true)
+    }
+
+    /// Days in a row the challenge was taken, up to `today`. Not having taken it
+    /// yet today doesn't break the streak.
+    pub fn streak(&self, today: Day) -> io::Result<u32> {
+        let days: Vec<Day> = self.attempts()?.into_iter().map(|(day, _)| day).collect();
+        let mut day = if days.contains(&today) {
+            today
+        } else {
+            today.previous()
+        };
+        let mut streak = 0;
+        while days.contains(&day) {
+            streak += 1;
+            if day == Day(0) {
+                break;
+            }
+            day = day.previous();
+        }
+        Ok(streak)
+    }
+}
+
+fn parse_attempt(line: &str) -> Option<(Day, Score)> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() != 6 {
+        return None;
+    }
+    let score = Score {
+        wpm: f32::from_str(fields[1]).ok()?,
+        accuracy: f32::from_str(fields[2]).ok()?,
+        correct: u32::from_str(fields[3]).ok()?,
+        incorrect: u32::from_str(fields[4]).ok()?,
+        time: u32::from_str(fields[5]).ok()?,
+        letters: StrokeCount::default(),
+        symbols: StrokeCount::default(),
+    };
+    Some((Day::from_str(fields[0]).ok()?, score))
+}
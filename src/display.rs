@@ -1,18 +1,31 @@
+use crate::config::Caret;
 use crate::layout::{Layout, Print};
+use crate::terminal::Output;
+use crate::timer::Clock;
 use crate::types::Result;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
 use termion::clear;
 use termion::cursor;
-use termion::terminal_size;
+
+const DEFAULT_CURSOR_STYLE: &str = "\u{001b}[0 q";
+const FLASH_ON: &str = "\u{001b}[?5h";
+const FLASH_OFF: &str = "\u{001b}[?5l";
 
 pub struct Display {
+    output: Box<dyn Output>,
+    clock: Rc<dyn Clock>,
     col_offset: u16,
     bounds_set: bool,
     layout_size: (u16, u16),
 }
 
 impl Display {
-    pub fn new() -> Self {
+    pub fn new(output: Box<dyn Output>, clock: Rc<dyn Clock>) -> Self {
         Self {
+            output,
+            clock,
             bounds_set: false,
             col_offset: 0,
             layout_size: (0, 0),
@@ -21,7 +34,7 @@ impl Display {
 
     fn set_bounds(&mut self, layout: &impl Layout) {
         if !self.bounds_set {
-            let (_, term_cols) = terminal_size().unwrap();
+            let (term_cols, _) = self.output.size();
             let mut index = 0;
             let mut total_rows = 0;
             let mut total_cols = 0;
@@ -35,7 +48,8 @@ impl Display {
                 index += 1;
             }
             self.layout_size = (total_rows, total_cols);
-            let col_offset = (term_cols - total_cols) / 2 - total_cols / 2;
+            let col_offset =
+                (term_cols.saturating_sub(total_cols) / 2).saturating_sub(total_cols / 2);
             if col_offset > 0 {
                 self.col_offset = col_offset;
             }
@@ -43,32 +57,69 @@ impl Display {
         }
     }
 
-    fn render_internal(&self, layout: &impl Layout, clear: bool) -> Result<()> {
+    fn render_internal(&mut self, layout: &impl Layout, clear: bool) -> Result<()> {
         let mut index = 0;
 
         if clear {
-            Self::clear();
+            self.clear()?;
         }
 
         while let Some((rows, _)) = layout.get_row_size(index) {
             let components = layout.get_row(index).unwrap();
             for x in 0..rows {
                 // print!("{:1$}", " ", self.col_offset as usize);
-                components.iter().for_each(|component| {
-                    component.print(x);
-                });
-                println!();
-                print!("{}{}", clear::CurrentLine, cursor::Left(100));
+                for component in components {
+                    component.print(&mut self.output, x)?;
+                }
+                writeln!(self.output)?;
+                write!(self.output, "{}{}", clear::CurrentLine, cursor::Left(100))?;
             }
 
             index += 1;
         }
 
+        self.output.flush()?;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        write!(self.output, "{}{}", clear::All, cursor::Goto(1, 1))?;
+        Ok(())
+    }
+
+    pub fn set_raw_mode(&mut self, raw: bool) -> Result<()> {
+        self.output.set_raw_mode(raw)?;
+        Ok(())
+    }
+
+    /// Moves the terminal cursor to `(row, col)` of the rendered layout (0-based)
+    /// and shapes it according to `caret`.
+    pub fn place_caret(&mut self, caret: &Caret, (row, col): (u16, u16)) -> Result<()> {
+        match caret {
+            Caret::Block => write!(self.output, "{}{}", cursor::Show, cursor::SteadyBlock)?,
+            Caret::Underline => write!(self.output, "{}{}", cursor::Show, cursor::SteadyUnderline)?,
+            Caret::Bar => write!(self.output, "{}{}", cursor::Show, cursor::SteadyBar)?,
+            Caret::None => write!(self.output, "{}", cursor::Hide)?,
+        }
+        write!(self.output, "{}", cursor::Goto(col + 1, row + 1))?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    /// Rings the terminal bell and briefly flashes the screen.
+    pub fn notify(&mut self) -> Result<()> {
+        write!(self.output, "\u{0007}{}", FLASH_ON)?;
+        self.output.flush()?;
+        self.clock.sleep(Duration::from_millis(100));
+        write!(self.output, "{}", FLASH_OFF)?;
+        self.output.flush()?;
         Ok(())
     }
 
-    pub fn clear() {
-        print!("{}{}", clear::BeforeCursor, cursor::Goto(1, 1));
+    pub fn reset_caret(&mut self) -> Result<()> {
+        write!(self.output, "{}{}", DEFAULT_CURSOR_STYLE, cursor::Show)?;
+        self.output.flush()?;
+        Ok(())
     }
 
     pub fn render_no_clear(&mut self, layout: &impl Layout) -> Result<()> {
@@ -81,3 +132,14 @@ impl Display {
         self.render_internal(layout, true)
     }
 }
+
+/// Lets modes print their own messages on the end screen.
+impl Write for Display {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
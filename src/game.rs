@@ -1,205 +1,523 @@
-use crate::Component;
-use crate::Display;
-use crate::Layout;
-use crate::Timer;
-use crate::WordFeed;
-use crate::WordQueue;
+use crate::components::{Component, Layout};
+use crate::config::Config;
+use crate::display::Display;
+use crate::layout::Layout as _;
+use crate::mode::{spinner, Key, Mode, OnFinish, Progress, Session, MAX_PROGRESS_LINES, TICK};
+use crate::output::{self, Dump, OutputReader, Stderr};
+use crate::pty::Pty;
+use crate::render;
+use crate::replay::Replay;
+use crate::score::Score;
+use crate::terminal::{Input, Output, Stdin, Stdout};
+use crate::timer::{Clock, SystemClock};
+use crate::types::Result;
 use std::collections::HashMap;
-use std::io;
+use std::fs;
+use std::io::{self, Write};
+use std::mem;
+use std::os::unix::process::ExitStatusExt;
 use std::process;
-use std::thread;
+use std::rc::Rc;
 use std::time;
-use termion::cursor;
-use termion::event;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
 
-pub trait Mode {}
+const QUIT_KEY: Key = Key::Ctrl('c');
+const PROGRESS_WIDTH: usize = 60;
+const FOCUS_KEY: Key = Key::Ctrl('t');
+const TASK_NAME_WIDTH: usize = 30;
+/// Wait status standing in for a task that couldn't be spawned.
+const NOT_STARTED: i32 = output::NOT_STARTED << 8;
 
-pub struct TimeMode {
-    pub time: u32,
+/// A command to run in command mode, with the name it is shown under.
+pub struct Task {
+    pub name: String,
+    pub command: process::Command,
 }
 
+/// Plays until the commands finish, and then as long as `on_finish` allows. Ends
+/// with the exit status of the last command that ran.
 pub struct CommandMode {
-    pub command: process::Command,
+    tasks: Vec<Task>,
+    tail: usize,
+    stderr: Stderr,
+    pty: bool,
+    on_finish: OnFinish,
+    dump: Dump,
+    output: OutputReader,
+    errors: OutputReader,
+    child: Option<process::Child>,
+    terminal: Option<Pty>,
+    statuses: Vec<process::ExitStatus>,
+    focus: bool,
+    started: time::Duration,
+    finished_at: Option<u32>,
+    last_frame: (usize, char, u32),
 }
 
-impl Mode for TimeMode {}
-impl Mode for CommandMode {}
+impl CommandMode {
+    pub fn new(tasks: Vec<Task>, on_finish: OnFinish) -> Self {
+        Self {
+            tasks,
+            tail: 3,
+            stderr: Stderr::Interleave,
+            pty: false,
+            on_finish,
+            dump: Dump::Print,
+            output: OutputReader::new(),
+            errors: OutputReader::new(),
+            child: None,
+            terminal: None,
+            statuses: Vec::new(),
+            focus: false,
+            started: time::Duration::ZERO,
+            finished_at: None,
+            last_frame: (0, ' ', 0),
+        }
+    }
 
-pub struct Game<'a, M: Mode> {
-    mode: M,
-    word_queue: WordQueue<'a>,
-    layout: Layout,
-    display: Display,
-    timer: Timer,
-}
+    pub fn set_tail(&mut self, tail: usize) {
+        self.tail = tail;
+    }
 
-impl<'a, M: Mode> Game<'a, M> {
-    pub fn new(mode: M) -> Self {
-        new_game(mode)
+    pub fn set_stderr(&mut self, stderr: Stderr) {
+        self.stderr = stderr;
     }
 
-    fn update_layout(&mut self) {
-        let rows = self.word_queue.get_parsed();
-        self.layout.update("words", ("row1", rows.get(0).unwrap()));
-        self.layout.update("words", ("row2", rows.get(1).unwrap()));
-        self.layout.update(
-            "word",
-            ("word", &self.word_queue.get_current_word_ref().actual()[..]),
-        );
-    }
-
-    fn process_key(&mut self, key: event::Key) -> Result<(), ()> {
-        match key {
-            event::Key::Ctrl('c') => Err(()),
-            _ => {
-                if !self.timer.running() {
-                    self.timer.start()
-                }
-                self.word_queue.register_key(key);
-                self.update_layout();
-                self.display.render(&self.layout).unwrap();
-                Ok(())
+    pub fn set_pty(&mut self, pty: bool) {
+        self.pty = pty;
+    }
+
+    pub fn set_dump(&mut self, dump: Dump) {
+        self.dump = dump;
+    }
+
+    /// Starts the task at `index`, feeding its output to the readers.
+    fn spawn(&mut self, index: usize) -> io::Result<()> {
+        let command = &mut self.tasks[index].command;
+        let terminal = if self.pty {
+            Some(Pty::attach(command)?)
+        } else {
+            None
+        };
+        let mut child = command.spawn()?;
+
+        if let Some(terminal) = &terminal {
+            // The command keeps its copies of the terminal open until replaced, and the
+            // output would never reach EOF.
+            command
+                .stdin(process::Stdio::null())
+                .stdout(process::Stdio::null())
+                .stderr(process::Stdio::null());
+            self.output.attach(terminal.reader()?);
+        }
+        if let Some(stdout) = child.stdout.take() {
+            self.output.attach(stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            match self.stderr {
+                Stderr::Separate => self.errors.attach(stderr),
+                _ => self.output.attach(stderr),
             }
         }
+
+        self.child = Some(child);
+        self.terminal = terminal;
+        self.focus = false;
+        Ok(())
     }
-}
 
-impl<'a> Game<'a, TimeMode> {
-    pub fn start(&mut self) {
-        self.timer.set(self.mode.time);
-        let mut _stdout = io::stdout().into_raw_mode().unwrap();
-        let mut stdin = termion::async_stdin().keys();
-        self.update_layout();
-        self.display.render(&self.layout).unwrap();
+    /// Records the exit of the running task, starting the next one if it
+    /// succeeded. Returns whether no more tasks are left to run.
+    fn poll(&mut self) -> bool {
+        let status = match self.child.as_mut().map(|child| child.try_wait()) {
+            Some(Ok(Some(status))) => status,
+            _ => return false,
+        };
+        self.statuses.push(status);
+        let next = self.statuses.len();
+        if status.success() && next < self.tasks.len() {
+            if self.spawn(next).is_ok() {
+                return false;
+            }
+            self.statuses
+                .push(process::ExitStatus::from_raw(NOT_STARTED));
+        }
+        true
+    }
 
-        loop {
-            thread::sleep(time::Duration::from_millis(50));
+    fn status(&self, session: &Session) -> String {
+        if self.finished_at.is_some() {
+            let outcome = match self.statuses.last() {
+                Some(status) if status.success() => "✓ your process finished successfully",
+                _ => "✗ your process failed",
+            };
+            return self.on_finish.hint(session, outcome);
+        }
 
-            if self.timer.is_limit() {
-                break;
+        let running = self.statuses.len();
+        let name: String = self.tasks[running]
+            .name
+            .chars()
+            .take(TASK_NAME_WIDTH)
+            .collect();
+        let task = if self.tasks.len() > 1 {
+            format!("[{}/{}] {}", running + 1, self.tasks.len(), name)
+        } else {
+            name
+        };
+        let focus = match (&self.terminal, self.focus) {
+            (None, _) => "",
+            (Some(_), false) => "  [ctrl+t: type into command]",
+            (Some(_), true) => "  [typing into command, ctrl+t: back to practice]",
+        };
+        format!(
+            "{} {} running for {}s, {} of output{}",
+            spinner(self.elapsed(session)),
+            task,
+            self.elapsed(session).as_secs(),
+            output::format_bytes(self.output.bytes()),
+            focus
+        )
+    }
+
+    fn elapsed(&self, session: &Session) -> time::Duration {
+        session.clock.now() - self.started
+    }
+
+    fn update_progress(&self, session: &mut Session) {
+        let status = self.status(session);
+        if let Some(progress) = &mut session.progress {
+            progress.lines = self.output.tail(self.tail, PROGRESS_WIDTH);
+            progress.status = status;
+        }
+    }
+
+    /// Hands the collected output over as chosen by `dump`. Expects the terminal
+    /// to be out of raw mode, so long lines wrap and scroll as usual.
+    fn print_output(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        let mut text = mem::take(&mut self.output).finish();
+        let stderr = mem::take(&mut self.errors).finish();
+        if !stderr.is_empty() {
+            if !text.is_empty() && !text.ends_with(b"\n") {
+                text.push(b'\n');
             }
+            text.extend_from_slice(b"--- stderr ---\n");
+            text.extend(stderr);
+        }
 
-            if let Some(Ok(key)) = stdin.next() {
-                if let Err(_) = self.process_key(key) {
-                    break;
+        let location = match &self.dump {
+            Dump::Print => {
+                out.write_all(&text)?;
+                if !text.ends_with(b"\n") {
+                    writeln!(out)?;
                 }
+                "The output is above.".to_string()
             }
+            Dump::Pager => match output::page(&text) {
+                Ok(()) => "The output was shown in your pager.".to_string(),
+                Err(err) => {
+                    writeln!(out, "error: failed to start pager: {}", err)?;
+                    out.write_all(&text)?;
+                    "The output is above.".to_string()
+                }
+            },
+            Dump::File(path) => match fs::write(path, &text) {
+                Ok(()) => format!("The output was written to {}.", path.display()),
+                Err(err) => {
+                    writeln!(out, "error: failed to write {}: {}", path.display(), err)?;
+                    out.write_all(&text)?;
+                    "The output is above.".to_string()
+                }
+            },
+        };
+
+        if self.tasks.len() > 1 {
+            for (i, task) in self.tasks.iter().enumerate() {
+                let outcome = match self.statuses.get(i) {
+                    Some(status) if status.into_raw() == NOT_STARTED => {
+                        "failed to start".to_string()
+                    }
+                    Some(status) => output::describe_status(*status),
+                    None => "was skipped".to_string(),
+                };
+                writeln!(out, "{} {}", task.name, outcome)?;
+            }
+            writeln!(out, "{} Here's your score:", location)?;
+        } else if let Some(status) = self.statuses.last() {
+            writeln!(
+                out,
+                "Your process {}. {} Here's your score:",
+                output::describe_status(*status),
+                location
+            )?;
         }
+        Ok(())
+    }
+}
+
+impl Mode for CommandMode {
+    type Outcome = Option<process::ExitStatus>;
 
-        self.end();
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        self.output = OutputReader::new();
+        self.errors = OutputReader::new();
+        self.started = session.clock.now();
+        self.spawn(0).map_err(|err| {
+            io::Error::new(err.kind(), format!("failed to start command: {}", err))
+        })?;
+        session.progress = Some(Progress::new(self.tail));
+        self.update_progress(session);
+        Ok(())
     }
 
-    fn end(&mut self) {
-        let score_layout = build_score_layout(self);
-        self.display.render(&score_layout).unwrap();
+    fn on_tick(&mut self, session: &mut Session) -> bool {
+        if self.finished_at.is_none() && self.poll() {
+            self.finished_at = Some(self.on_finish.start(session));
+            self.focus = false;
+        }
+
+        // The spinner stops along with the commands.
+        let spinner = match self.finished_at {
+            Some(_) => ' ',
+            None => spinner(self.elapsed(session)),
+        };
+        let frame = (self.output.bytes(), spinner, session.timer.passed());
+        if frame == self.last_frame {
+            return false;
+        }
+        self.last_frame = frame;
+        self.update_progress(session);
+        true
     }
-}
 
-impl<'a> Game<'a, CommandMode> {
-    pub fn start(&mut self) {
-        let mut child: process::Child;
-        if let Ok(c) = self.mode.command.spawn() {
-            child = c;
+    fn on_key(&mut self, session: &mut Session, key: Key) -> bool {
+        if key == FOCUS_KEY && self.terminal.is_some() && self.finished_at.is_none() {
+            self.focus = !self.focus;
+            self.update_progress(session);
+            true
+        } else if self.focus {
+            if let Some(terminal) = &mut self.terminal {
+                terminal.send_key(key).ok();
+            }
+            true
         } else {
-            println!("error: failed to start command");
-            return;
+            false
         }
-        let mut _stdout = io::stdout().into_raw_mode().unwrap();
-        let mut stdin = termion::async_stdin().keys();
-        self.update_layout();
-        self.display.render(&self.layout).unwrap();
+    }
+
+    fn is_finished(&self, session: &Session) -> bool {
+        self.finished_at
+            .is_some_and(|words| self.on_finish.is_over(session, words))
+    }
+
+    /// The output of commands that finished is kept even if the player quits
+    /// afterwards; only a command killed by quitting is left without it.
+    fn on_end(
+        &mut self,
+        _session: &mut Session,
+        out: &mut dyn Write,
+        _quit: bool,
+    ) -> Self::Outcome {
+        if self.finished_at.is_some() {
+            self.print_output(out).unwrap();
+        } else if let Some(child) = &mut self.child {
+            child.kill().ok();
+            if let Ok(status) = child.wait() {
+                self.statuses.push(status);
+            }
+        }
+        self.statuses.last().copied()
+    }
+}
+
+/// A game played in the terminal by the rules of a `Mode`.
+pub struct Game<'a, M: Mode> {
+    mode: M,
+    session: Session<'a>,
+    input: Box<dyn Input>,
+    layout: Layout,
+    display: Display,
+    ghost: Option<usize>,
+}
+
+impl<'a, M: Mode> Game<'a, M> {
+    /// A game played in the terminal.
+    pub fn new(mode: M, config: Config) -> Self {
+        Self::with_terminal(
+            mode,
+            config,
+            Box::new(Stdin::new()),
+            Box::new(Stdout::new()),
+            Rc::new(SystemClock::new()),
+        )
+    }
+
+    /// A game played with keys from `input`, drawn on `output` and timed by
+    /// `clock`, e.g. to script one in tests.
+    pub fn with_terminal(
+        mode: M,
+        config: Config,
+        input: Box<dyn Input>,
+        output: Box<dyn Output>,
+        clock: Rc<dyn Clock>,
+    ) -> Self {
+        Game {
+            mode,
+            display: Display::new(output, clock.clone()),
+            session: Session::new(config, clock),
+            input,
+            layout: Layout {
+                layout: vec![vec![Component::new("words")], vec![Component::new("word")]],
+            },
+            ghost: None,
+        }
+    }
+
+    /// Whether the player started playing, the timer starting with the first key.
+    pub fn is_started(&self) -> bool {
+        self.session.timer.running()
+    }
+
+    pub fn score(&self) -> Score {
+        self.session.score()
+    }
+
+    /// The pace of the game played, to race against later.
+    pub fn replay(&self) -> Replay {
+        self.session.replay()
+    }
+
+    /// Plays until the mode is finished or the player quits, then shows the score.
+    /// The mode starts once the terminal is in raw mode, and ends out of it on a
+    /// cleared screen.
+    pub fn start(&mut self) -> Result<M::Outcome> {
+        // Raw mode comes first so that nothing the mode starts is left behind
+        // when the terminal can't be set up.
+        self.display.set_raw_mode(true)?;
+        if let Err(err) = self.mode.on_start(&mut self.session) {
+            self.display.set_raw_mode(false)?;
+            return Err(err.into());
+        }
+        if let Some(progress) = &self.session.progress {
+            self.layout
+                .layout
+                .insert(0, vec![Component::progress(progress.height)]);
+        }
+        self.update_words();
+        self.render();
+        let mut quit = false;
 
         loop {
-            thread::sleep(time::Duration::from_millis(50));
+            self.session.clock.sleep(TICK);
+
+            let ghost = self.session.ghost();
+            if ghost != self.ghost {
+                self.ghost = ghost;
+                self.update_words();
+                self.render();
+            }
 
-            if let Ok(Some(_)) = child.try_wait() {
-                self.print_output(child);
+            if self.mode.on_tick(&mut self.session) {
+                self.render();
+            }
+            if self.session.take_notification() {
+                self.display.notify()?;
+            }
+
+            if self.mode.is_finished(&self.session) {
                 break;
             }
 
-            if let Some(Ok(key)) = stdin.next() {
-                if let Err(_) = self.process_key(key) {
-                    child.kill().unwrap();
+            if let Some(key) = self.input.next_key() {
+                if self.mode.on_key(&mut self.session, key) {
+                    self.render();
+                } else if key == QUIT_KEY {
+                    quit = true;
                     break;
+                } else {
+                    if let Some(word) = self.session.type_key(key) {
+                        self.mode.on_word_complete(&mut self.session, &word);
+                    }
+                    self.update_words();
+                    self.render();
                 }
             }
         }
 
-        self.end();
+        self.display.reset_caret()?;
+        self.display.set_raw_mode(false)?;
+        self.display.clear()?;
+        let outcome = self.mode.on_end(&mut self.session, &mut self.display, quit);
+        let score_layout = build_score_layout(&self.session);
+        self.display.render_no_clear(&score_layout).unwrap();
+        Ok(outcome)
     }
 
-    fn print_output(&self, child: process::Child) {
-        if let Ok(output) = child.wait_with_output() {
-            Display::clear();
-            if let Ok(o) = String::from_utf8(output.stdout) {
-                for line in o.split('\n').into_iter() {
-                    println!("{}", line);
-                    print!("{}", cursor::Left(100));
-                }
+    fn update_words(&mut self) {
+        let word_queue = &self.session.word_queue;
+        let ghost = self
+            .session
+            .ghost()
+            .and_then(|strokes| word_queue.ghost_position(strokes));
+        let rows = render::rows(word_queue, self.session.config.highlight, ghost);
+        self.layout.update("words", ("row1", rows.first().unwrap()));
+        self.layout.update("words", ("row2", rows.get(1).unwrap()));
+        self.layout
+            .update("word", ("word", &word_queue.current_word().actual()[..]));
+    }
+
+    /// Draws the game, with the lines of the progress at the bottom of their space.
+    fn render(&mut self) {
+        if let Some(progress) = &self.session.progress {
+            let mut state = HashMap::new();
+            let lines = &progress.lines;
+            for line in 1..=MAX_PROGRESS_LINES {
+                let index = (line + lines.len()).checked_sub(MAX_PROGRESS_LINES + 1);
+                let text = index.map_or("", |i| lines[i].as_str());
+                state.insert(format!("line{}", line), text.to_string());
             }
-            print!("{}", cursor::Left(100));
-            println!("Your process has finished. The output is above. Here's your score:");
+            state.insert("status".to_string(), progress.status.clone());
+            self.layout.replace("progress", &state);
         }
-    }
 
-    fn end(&mut self) {
-        let score_layout = build_score_layout(self);
-        self.display.render_no_clear(&score_layout).unwrap();
+        self.display.render(&self.layout).unwrap();
+        if let Some((row, col)) = self.layout.get_placeholder_position("words", "row1") {
+            let caret = (row, col + self.session.word_queue.caret_offset());
+            self.display
+                .place_caret(&self.session.config.caret, caret)
+                .unwrap();
+        }
     }
 }
 
-fn build_score_layout<M: Mode>(game: &Game<M>) -> Layout {
-    let score = Component::new("score");
+fn build_score_layout(session: &Session) -> Layout {
+    let score = session.score();
     let mut score_state = HashMap::new();
 
-    let (correct, incorrect) = game.word_queue.words_count();
-
-    let raw_score_layout = vec![vec![score]];
-
-    let correct_stroke_count = game.word_queue.correct_stroke_count();
+    let mut raw_score_layout = vec![vec![Component::new("score")]];
+    if session.config.code.is_some() || session.config.text.is_some() {
+        raw_score_layout.push(vec![Component::new("strokes")]);
+    }
 
     let mut score_layout = Layout {
         layout: raw_score_layout,
     };
-    let accuracy = if correct + incorrect == 0 {
-        0.0
-    } else {
-        correct as f32 / (correct + incorrect) as f32 * 100.0
-    };
-    let wpm = if game.timer.passed() == 0 {
-        0.0
-    } else {
-        correct_stroke_count as f32 / 5.0 / game.timer.passed() as f32 * 60.0
-    };
 
-    score_state.insert("correct".to_string(), correct.to_string());
-    score_state.insert("incorrect".to_string(), incorrect.to_string());
-    score_state.insert("accuracy".to_string(), format!("{:.2}", accuracy));
-    score_state.insert("wpm".to_string(), format!("{:.0}", wpm));
-    score_state.insert("time".to_string(), game.timer.passed().to_string());
+    score_state.insert("correct".to_string(), score.correct.to_string());
+    score_state.insert("incorrect".to_string(), score.incorrect.to_string());
+    score_state.insert("accuracy".to_string(), format!("{:.2}", score.accuracy));
+    score_state.insert("wpm".to_string(), format!("{:.0}", score.wpm));
+    score_state.insert("time".to_string(), score.time.to_string());
 
     score_layout.replace("score", &score_state);
 
-    score_layout
-}
-
-fn new_game<'a, M: Mode>(mode: M) -> Game<'a, M> {
-    let feed = WordFeed::new();
-    let mut word_queue = WordQueue::new(feed);
-    let display = Display::new();
-    let layout = Layout {
-        layout: vec![vec![Component::new("words")], vec![Component::new("word")]],
-    };
-    let timer = Timer::new(60);
+    score_layout.update(
+        "strokes",
+        ("letters", &format!("{:.2}", score.letters.accuracy())),
+    );
+    score_layout.update(
+        "strokes",
+        ("symbols", &format!("{:.2}", score.symbols.accuracy())),
+    );
 
-    word_queue.init();
-    Game {
-        mode,
-        word_queue,
-        layout,
-        display,
-        timer,
-    }
+    score_layout
 }
@@ -0,0 +1,52 @@
+#[derive(Debug, Clone, Copy)]
+pub struct Language {
+    pub name: &'static str,
+    pub words: &'static str,
+}
+
+pub const ENGLISH: Language = Language {
+    name: "english",
+    words: include_str!("ngsl.txt"),
+};
+
+/// Word lists compiled into this binary, ordered by descending word frequency.
+pub const LANGUAGES: &[Language] = &[
+    ENGLISH,
+    #[cfg(feature = "french")]
+    Language {
+        name: "french",
+        words: include_str!("lists/french.txt"),
+    },
+    #[cfg(feature = "german")]
+    Language {
+        name: "german",
+        words: include_str!("lists/german.txt"),
+    },
+    #[cfg(feature = "italian")]
+    Language {
+        name: "italian",
+        words: include_str!("lists/italian.txt"),
+    },
+    #[cfg(feature = "polish")]
+    Language {
+        name: "polish",
+        words: include_str!("lists/polish.txt"),
+    },
+    #[cfg(feature = "portuguese")]
+    Language {
+        name: "portuguese",
+        words: include_str!("lists/portuguese.txt"),
+    },
+    #[cfg(feature = "spanish")]
+    Language {
+        name: "spanish",
+        words: include_str!("lists/spanish.txt"),
+    },
+];
+
+pub fn find(name: &str) -> Option<Language> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.name == name)
+        .copied()
+}
@@ -1,15 +1,36 @@
 use std::collections::HashMap;
+use std::io::{self, Write};
 
 pub trait Layout {
     type Component: Template + State + Transform + Print;
     fn get_ref_mut(&mut self, component_id: &str) -> Option<&mut Self::Component>;
     fn get_row(&self, row: u16) -> Option<&[Self::Component]>;
     fn get_row_size(&self, row: u16) -> Option<(u16, u16)> {
-        if let Some(r) = self.get_row(row) {
-            Some(get_row_size(r))
-        } else {
-            None
+        self.get_row(row).map(get_row_size)
+    }
+    fn get_placeholder_position(&self, component_id: &str, key: &str) -> Option<(u16, u16)> {
+        let placeholder = format!("{{{{{}}}}}", key);
+        let mut index = 0;
+        let mut row_offset = 0;
+        while let Some(components) = self.get_row(index) {
+            let mut col_offset = 0;
+            for component in components {
+                if component.id() == component_id {
+                    return component
+                        .template()
+                        .iter()
+                        .enumerate()
+                        .find_map(|(line, row)| {
+                            row.find(&placeholder)
+                                .map(|col| (row_offset + line as u16, col_offset + col as u16))
+                        });
+                }
+                col_offset += component.size().1;
+            }
+            row_offset += get_row_size(components).0;
+            index += 1;
         }
+        None
     }
 }
 
@@ -33,12 +54,13 @@ pub trait Transform: Template + State {
 }
 
 pub trait Print: Transform {
-    fn print(&self, row: u16) {
+    fn print(&self, out: &mut dyn Write, row: u16) -> io::Result<()> {
         if let Some(str) = self.template().get(row as usize) {
             if let Some(t_str) = self.transform(str, &self.state()) {
-                print!("{}", t_str);
+                write!(out, "{}", t_str)?;
             };
         };
+        Ok(())
     }
 }
 
@@ -47,9 +69,9 @@ fn transform_row(row: &str, state: &HashMap<String, String>) -> Option<String> {
     for (key, value) in state {
         let mut placeholder = String::new();
         placeholder.push_str("{{");
-        placeholder.push_str(&key);
+        placeholder.push_str(key);
         placeholder.push_str("}}");
-        s_row = s_row.replace(&placeholder, &value);
+        s_row = s_row.replace(&placeholder, value);
     }
     Some(s_row)
 }
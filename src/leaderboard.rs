@@ -0,0 +1,181 @@
+use crate::config::{self, Config};
+use crate::language;
+use crate::race::Standings;
+use crate::score::Score;
+use crate::words::{Difficulty, StrokeCount};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The mode a timed game on `config`'s words is ranked under: "time60" with the
+/// default words, or e.g. "time30-top200-punctuation" with others. Games on code
+/// or text aren't ranked, as players bring their own.
+pub fn mode(time: u32, config: &Config) -> Option<String> {
+    if config.code.is_some() || config.text.is_some() {
+        return None;
+    }
+    let mut mode = format!("time{}", time);
+    if config.language.name != language::ENGLISH.name {
+        mode += &format!("-{}", config.language.name);
+    }
+    let default = Difficulty::default();
+    let ranks = &config.difficulty.ranks;
+    if ranks.start != default.ranks.start {
+        mode += &format!("-ranks{}-{}", ranks.start + 1, ranks.end);
+    } else if ranks.end != default.ranks.end {
+        mode += &format!("-top{}", ranks.end);
+    }
+    let lengths = &config.difficulty.lengths;
+    if lengths.start() != default.lengths.start() {
+        mode += &format!("-min{}", lengths.start());
+    }
+    if lengths.end() != default.lengths.end() {
+        mode += &format!("-max{}", lengths.end());
+    }
+    if config.punctuation {
+        mode += "-punctuation";
+    }
+    if config.numbers {
+        mode += "-numbers";
+    }
+    Some(mode)
+}
+
+/// A game on the leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    /// What was played, e.g. "time60".
+    pub mode: String,
+    pub score: Score,
+    /// Seconds since the Unix epoch.
+    pub played: u64,
+}
+
+impl Entry {
+    /// A game that has just been played.
+    pub fn new(name: &str, mode: &str, score: Score) -> Self {
+        let played = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Self {
+            name: name.to_string(),
+            mode: mode.to_string(),
+            score,
+            played,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.name,
+            self.mode,
+            self.played,
+            self.score.wpm,
+            self.score.accuracy,
+            self.score.correct,
+            self.score.incorrect,
+            self.score.time
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 8 {
+            return None;
+        }
+        Some(Self {
+            name: fields[0].to_string(),
+            mode: fields[1].to_string(),
+            played: u64::from_str(fields[2]).ok()?,
+            score: Score {
+                wpm: f32::from_str(fields[3]).ok()?,
+                accuracy: f32::from_str(fields[4]).ok()?,
+                correct: u32::from_str(fields[5]).ok()?,
+                incorrect: u32::from_str(fields[6]).ok()?,
+                time: u32::from_str(fields[7]).ok()?,
+                letters: StrokeCount::default(),
+                symbols: StrokeCount::default(),
+            },
+        })
+    }
+}
+
+/// Games of everyone using the same file, e.g. on a shared mount, one per line.
+pub struct Leaderboard {
+    path: PathBuf,
+}
+
+impl Leaderboard {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// `leaderboard` in the data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        config::data_dir().map(|dir| dir.join("leaderboard"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the game holding an exclusive lock on the file, so players sharing
+    /// it, even from other machines, don't mix up their lines.
+    pub fn record(&self, entry: &Entry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // Released once the file is closed.
+        file.lock()?;
+        file.write_all(entry.to_line().as_bytes())
+    }
+
+    /// Every game recorded, skipping the lines that can't be read. Games being
+    /// recorded meanwhile are waited for.
+    pub fn entries(&self) -> io::Result<Vec<Entry>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        file.lock_shared()?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        Ok(text.lines().filter_map(Entry::from_line).collect())
+    }
+
+    /// The `n` players with the fastest games of `mode`, each with their best.
+    pub fn top(&self, mode: &str, n: usize) -> io::Result<Standings> {
+        let mut best: HashMap<String, Score> = HashMap::new();
+        for entry in self.entries()? {
+            if entry.mode != mode {
+                continue;
+            }
+            let score = best.entry(entry.name).or_insert(entry.score);
+            if entry.score.wpm > score.wpm {
+                *score = entry.score;
+            }
+        }
+
+        let mut all = Standings::new();
+        for (name, score) in best {
+            all.add(&name, score);
+        }
+        let mut top = Standings::new();
+        for (name, score) in all.ranked().into_iter().take(n) {
+            top.add(name, *score);
+        }
+        Ok(top)
+    }
+}
@@ -0,0 +1,57 @@
+//! Terminal typing practice.
+//!
+//! The typing engine is independent of the terminal: a [`WordQueue`](words::WordQueue)
+//! takes the typed characters and tells how each word and grapheme stands, and
+//! [`Score`](score::Score) sums them up. The game modes in [`mode`] hold the rules,
+//! like the timer and when a game is over, and are played on a
+//! [`Session`](mode::Session) by feeding it [`Key`](mode::Key)s. Rendering and
+//! the terminal front end sit behind the default `tui` feature, which termion,
+//! libc and the `ftyper` binary come with.
+//!
+//! ```
+//! use ftyper::language::ENGLISH;
+//! use ftyper::score::Score;
+//! use ftyper::words::{Difficulty, WordFeed, WordQueue};
+//!
+//! let mut queue = WordQueue::new(WordFeed::new(ENGLISH.words, &Difficulty::default()));
+//! queue.init();
+//! let word = queue.current_word().expected();
+//! word.chars().for_each(|c| queue.type_char(c));
+//! queue.type_char(' ');
+//!
+//! let score = Score::new(&queue, 60);
+//! assert_eq!((score.correct, score.incorrect), (1, 0));
+//! ```
+
+pub mod code;
+#[cfg(feature = "tui")]
+pub mod components;
+pub mod config;
+pub mod daily;
+#[cfg(feature = "tui")]
+pub mod display;
+#[cfg(feature = "tui")]
+pub mod game;
+pub mod language;
+#[cfg(feature = "tui")]
+pub mod layout;
+pub mod leaderboard;
+pub mod mode;
+pub mod net;
+#[cfg(feature = "tui")]
+pub mod output;
+#[cfg(feature = "tui")]
+pub mod pty;
+pub mod race;
+#[cfg(feature = "tui")]
+pub mod render;
+pub mod replay;
+pub mod score;
+#[cfg(feature = "tui")]
+pub mod terminal;
+pub mod text;
+pub mod timer;
+#[cfg(feature = "tui")]
+pub mod types;
+pub mod watch;
+pub mod words;
@@ -0,0 +1,548 @@
+use crate::code::CodeFeed;
+use crate::config::Config;
+use crate::replay::Replay;
+use crate::score::Score;
+use crate::text::TextFeed;
+use crate::timer::{Clock, Timer};
+use crate::watch::Watch;
+use crate::words::{Difficulty, LiveWord, WordFeed, WordQueue};
+use std::io::{self, Write};
+use std::mem;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// How often a game is checked on, and how long a front end waits between keys.
+pub const TICK: Duration = Duration::from_millis(50);
+/// Most lines a mode can show above the words, besides its status line.
+pub const MAX_PROGRESS_LINES: usize = 5;
+const FINISH_KEYS: [Key; 2] = [Key::Esc, Key::Ctrl('d')];
+const STATS_KEY: Key = Key::Ctrl('s');
+const SURVIVAL_START_TIME: u32 = 10;
+const SURVIVAL_BONUS: i32 = 1;
+const SURVIVAL_PENALTY: i32 = -2;
+/// Seconds of play between difficulty levels.
+const SURVIVAL_LEVEL_TIME: u32 = 15;
+/// Most frequent words to pick from on the first level, doubled with each level.
+const SURVIVAL_RANKS: usize = 250;
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// A key pressed while playing, whatever it was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Alt(char),
+    Backspace,
+    Delete,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    /// Any key the game has no use for.
+    Other,
+}
+
+/// The rules of a game. A front end, like `game::Game` in the terminal, plays one
+/// by calling these hooks on every tick and key, and `Session::type_key` with the
+/// keys the mode didn't take.
+pub trait Mode {
+    /// What the game ends with, besides the score.
+    type Outcome;
+
+    /// Called once before the first tick. An error ends the game before it is
+    /// played.
+    fn on_start(&mut self, _session: &mut Session) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called on every tick. Returns whether the screen needs to be redrawn.
+    fn on_tick(&mut self, _session: &mut Session) -> bool {
+        false
+    }
+
+    /// Gets every key before it is typed. Returns whether the mode took it.
+    fn on_key(&mut self, _session: &mut Session, _key: Key) -> bool {
+        false
+    }
+
+    /// Called with each word typed, once the next one became current.
+    fn on_word_complete(&mut self, _session: &mut Session, _word: &LiveWord) {}
+
+    fn is_finished(&self, session: &Session) -> bool;
+
+    /// Called once the game is over, just before the score is shown, with `out`
+    /// to show messages of its own on. `quit` tells whether the player quit early.
+    fn on_end(&mut self, session: &mut Session, out: &mut dyn Write, quit: bool) -> Self::Outcome;
+}
+
+/// Lines a mode shows above the words, with a status line under them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    /// Lines kept for `lines`, up to `MAX_PROGRESS_LINES`.
+    pub height: usize,
+    /// Shown at the bottom of the space kept for them.
+    pub lines: Vec<String>,
+    pub status: String,
+}
+
+impl Progress {
+    pub fn new(height: usize) -> Self {
+        Self {
+            height: height.min(MAX_PROGRESS_LINES),
+            ..Self::default()
+        }
+    }
+}
+
+/// Everything a mode can look at and change while the game is played.
+pub struct Session<'a> {
+    pub config: Config,
+    pub word_queue: WordQueue<'a>,
+    pub timer: Timer,
+    pub clock: Rc<dyn Clock>,
+    /// Shown above the words, for modes that have something to tell.
+    pub progress: Option<Progress>,
+    /// Milliseconds into the game of each correct keystroke.
+    strokes: Vec<u32>,
+    bell: bool,
+}
+
+impl<'a> Session<'a> {
+    /// A game on `config`'s words, timed by `clock`.
+    pub fn new(config: Config, clock: Rc<dyn Clock>) -> Self {
+        let mut word_queue = if let Some(sources) = &config.code {
+            let mut feed = CodeFeed::new(sources.clone());
+            if let Some(seed) = config.seed {
+                feed.set_seed(seed);
+            }
+            WordQueue::new(feed)
+        } else if let Some(text) = &config.text {
+            WordQueue::new(TextFeed::new(text))
+        } else {
+            let mut feed = WordFeed::new(config.language.words, &config.difficulty);
+            feed.set_punctuation(config.punctuation);
+            feed.set_numbers(config.numbers);
+            if let Some(seed) = config.seed {
+                feed.set_seed(seed);
+            }
+            WordQueue::new(feed)
+        };
+        word_queue.init();
+
+        Self {
+            config,
+            word_queue,
+            timer: Timer::new(60, clock.clone()),
+            clock,
+            progress: None,
+            strokes: Vec::new(),
+            bell: false,
+        }
+    }
+
+    /// Types `key` into the words, starting the timer with the first key. Returns
+    /// the word it completed, if any.
+    pub fn type_key(&mut self, key: Key) -> Option<LiveWord> {
+        if !self.timer.running() {
+            self.timer.start()
+        }
+        let correct = correct_strokes(&self.word_queue);
+        match key {
+            Key::Char(c) => self.word_queue.type_char(c),
+            Key::Backspace => self.word_queue.delete(),
+            _ => {}
+        }
+        if correct_strokes(&self.word_queue) > correct {
+            let elapsed = self.timer.elapsed().as_millis() as u32;
+            self.strokes.push(elapsed);
+        }
+        self.word_queue.take_completed()
+    }
+
+    pub fn score(&self) -> Score {
+        Score::new(&self.word_queue, self.timer.passed())
+    }
+
+    /// The pace of the game played, to race against later.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            wpm: self.score().wpm,
+            strokes: self.strokes.clone(),
+        }
+    }
+
+    /// Keystrokes made by the ghost caret so far, if there is one.
+    pub fn ghost(&self) -> Option<usize> {
+        match &self.config.pace {
+            Some(pace) if self.timer.running() => Some(pace.strokes_at(self.timer.elapsed())),
+            _ => None,
+        }
+    }
+
+    /// Sets the status line of the progress, for modes that show one.
+    pub fn set_status(&mut self, status: String) {
+        if let Some(progress) = &mut self.progress {
+            progress.status = status;
+        }
+    }
+
+    /// Asks the front end to get the player's attention, e.g. with the bell.
+    pub fn notify(&mut self) {
+        self.bell = true;
+    }
+
+    /// Whether the player is to be notified, clearing the request.
+    pub fn take_notification(&mut self) -> bool {
+        mem::take(&mut self.bell)
+    }
+}
+
+fn correct_strokes(word_queue: &WordQueue) -> u32 {
+    let (letters, symbols) = word_queue.stroke_counts();
+    letters.correct + symbols.correct
+}
+
+/// Spinner frame for something that has been going on for `elapsed`.
+pub fn spinner(elapsed: Duration) -> char {
+    let frame = elapsed.as_millis() / SPINNER_FRAME.as_millis();
+    SPINNER[frame as usize % SPINNER.len()]
+}
+
+pub struct TimeMode {
+    pub time: u32,
+}
+
+impl Mode for TimeMode {
+    type Outcome = ();
+
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        session.timer.set(self.time);
+        Ok(())
+    }
+
+    fn is_finished(&self, session: &Session) -> bool {
+        session.timer.is_limit()
+    }
+
+    fn on_end(&mut self, _session: &mut Session, _out: &mut dyn Write, _quit: bool) {}
+}
+
+/// Plays without a time limit until a finish key is pressed, with the stats
+/// shown only when asked for.
+#[derive(Default)]
+pub struct ZenMode {
+    finished: bool,
+    stats: bool,
+    last_frame: u32,
+}
+
+impl ZenMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update_status(&self, session: &mut Session) {
+        let status = if self.stats {
+            let score = session.score();
+            format!(
+                "{}s  correct: {}  incorrect: {}  accuracy: {:.0}%  speed: {:.0}wpm  [ctrl+s: hide]",
+                score.time, score.correct, score.incorrect, score.accuracy, score.wpm
+            )
+        } else {
+            "[esc: finish, ctrl+s: stats]".to_string()
+        };
+        session.set_status(status);
+    }
+}
+
+impl Mode for ZenMode {
+    type Outcome = ();
+
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        session.progress = Some(Progress::new(0));
+        self.update_status(session);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, session: &mut Session) -> bool {
+        let frame = session.timer.passed();
+        if !self.stats || frame == self.last_frame {
+            return false;
+        }
+        self.last_frame = frame;
+        self.update_status(session);
+        true
+    }
+
+    fn on_key(&mut self, session: &mut Session, key: Key) -> bool {
+        if FINISH_KEYS.contains(&key) {
+            self.finished = true;
+        } else if key == STATS_KEY {
+            self.stats = !self.stats;
+            self.update_status(session);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn on_word_complete(&mut self, session: &mut Session, _word: &LiveWord) {
+        if self.stats {
+            self.update_status(session);
+        }
+    }
+
+    fn is_finished(&self, _session: &Session) -> bool {
+        self.finished
+    }
+
+    fn on_end(&mut self, _session: &mut Session, _out: &mut dyn Write, _quit: bool) {}
+}
+
+/// Starts with a few seconds to play, gaining time with each correct word and
+/// losing some with each mistake, on words getting longer and rarer as it goes.
+pub struct SurvivalMode {
+    level: u32,
+    last_frame: (u32, u32),
+}
+
+impl SurvivalMode {
+    pub fn new() -> Self {
+        Self {
+            level: 1,
+            last_frame: (0, 0),
+        }
+    }
+
+    fn update_status(&self, session: &mut Session) {
+        let left = session.timer.limit().saturating_sub(session.timer.passed());
+        session.set_status(format!("{}s left  level {}", left, self.level));
+    }
+}
+
+impl Default for SurvivalMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Words of the top `SURVIVAL_RANKS << (level - 1)` ranks, a letter longer with
+/// every level until they are at least 7 letters long.
+fn survival_difficulty(level: u32) -> Difficulty {
+    let level = level.min(16) as usize;
+    Difficulty {
+        ranks: 0..SURVIVAL_RANKS << (level - 1),
+        lengths: (level + 1).min(7)..=level + 4,
+    }
+}
+
+impl Mode for SurvivalMode {
+    type Outcome = ();
+
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        session.timer.set(SURVIVAL_START_TIME);
+        session
+            .word_queue
+            .set_difficulty(&survival_difficulty(self.level));
+        session.word_queue.init();
+        session.progress = Some(Progress::new(0));
+        self.update_status(session);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, session: &mut Session) -> bool {
+        let level = session.timer.passed() / SURVIVAL_LEVEL_TIME + 1;
+        if level != self.level {
+            self.level = level;
+            session
+                .word_queue
+                .set_difficulty(&survival_difficulty(level));
+        }
+
+        let frame = (session.timer.passed(), session.timer.limit());
+        if frame == self.last_frame {
+            return false;
+        }
+        self.last_frame = frame;
+        self.update_status(session);
+        true
+    }
+
+    fn on_word_complete(&mut self, session: &mut Session, word: &LiveWord) {
+        if word.is_correct() {
+            session.timer.adjust(SURVIVAL_BONUS);
+        } else {
+            session.timer.adjust(SURVIVAL_PENALTY);
+        }
+        self.update_status(session);
+    }
+
+    fn is_finished(&self, session: &Session) -> bool {
+        session.timer.is_limit()
+    }
+
+    fn on_end(&mut self, session: &mut Session, out: &mut dyn Write, quit: bool) {
+        if !quit {
+            writeln!(
+                out,
+                "You survived for {}s and reached level {}. Here's your score:",
+                session.timer.passed(),
+                self.level
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// How the game goes on once what it waits on is done.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnFinish {
+    /// End right away.
+    Stop,
+    /// Let the word being typed be finished.
+    Word,
+    /// Play until the current minute is over.
+    Minute,
+    /// Carry on as a time mode game of the given length.
+    Continue(u32),
+}
+
+impl OnFinish {
+    /// Starts the end of the game, once what it waits on is done. Returns the
+    /// number of words completed so far.
+    pub fn start(self, session: &mut Session) -> u32 {
+        let passed = session.timer.passed();
+        match self {
+            OnFinish::Minute => session.timer.set((passed / 60 + 1) * 60),
+            OnFinish::Continue(time) => session.timer.set(passed + time),
+            _ => {}
+        }
+        if self != OnFinish::Stop {
+            session.notify();
+        }
+        let (correct, incorrect) = session.word_queue.words_count();
+        correct + incorrect
+    }
+
+    /// Whether the game is over, given the number of words completed when it
+    /// started finishing.
+    pub fn is_over(self, session: &Session, words_at_finish: u32) -> bool {
+        let (correct, incorrect) = session.word_queue.words_count();
+        match self {
+            OnFinish::Stop => true,
+            OnFinish::Word => {
+                correct + incorrect > words_at_finish
+                    || session.word_queue.current_word().actual().is_empty()
+            }
+            OnFinish::Minute => !session.timer.running() || session.timer.is_limit(),
+            OnFinish::Continue(_) => session.timer.running() && session.timer.is_limit(),
+        }
+    }
+
+    /// `outcome` followed by what's left to play.
+    pub fn hint(self, session: &Session, outcome: &str) -> String {
+        let left = session.timer.limit().saturating_sub(session.timer.passed());
+        match self {
+            OnFinish::Word => format!("{}, finish your word", outcome),
+            OnFinish::Continue(_) if !session.timer.running() => {
+                format!("{}, keep typing for {}s", outcome, left)
+            }
+            _ => format!("{}, {}s left", outcome, left),
+        }
+    }
+}
+
+/// Plays until something outside the game is done, like a process exiting, and
+/// then as long as `on_finish` allows.
+pub struct WatchMode {
+    watch: Box<dyn Watch>,
+    on_finish: OnFinish,
+    started: Duration,
+    finished_at: Option<u32>,
+    last_frame: (char, u32),
+}
+
+impl WatchMode {
+    pub fn new(watch: Box<dyn Watch>, on_finish: OnFinish) -> Self {
+        Self {
+            watch,
+            on_finish,
+            started: Duration::ZERO,
+            finished_at: None,
+            last_frame: (' ', 0),
+        }
+    }
+
+    fn status(&self, session: &Session) -> String {
+        match self.finished_at {
+            Some(_) => self
+                .on_finish
+                .hint(session, &format!("✓ {}", self.watch.done())),
+            None => format!(
+                "{} waiting for {} for {}s",
+                spinner(self.elapsed(session)),
+                self.watch.waiting_for(),
+                self.elapsed(session).as_secs()
+            ),
+        }
+    }
+
+    fn elapsed(&self, session: &Session) -> Duration {
+        session.clock.now() - self.started
+    }
+}
+
+impl Mode for WatchMode {
+    type Outcome = ();
+
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        self.started = session.clock.now();
+        session.progress = Some(Progress::new(0));
+        let status = self.status(session);
+        session.set_status(status);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, session: &mut Session) -> bool {
+        if self.finished_at.is_none() && self.watch.is_done() {
+            self.finished_at = Some(self.on_finish.start(session));
+        }
+
+        let spinner = match self.finished_at {
+            Some(_) => ' ',
+            None => spinner(self.elapsed(session)),
+        };
+        let frame = (spinner, session.timer.passed());
+        if frame == self.last_frame {
+            return false;
+        }
+        self.last_frame = frame;
+        let status = self.status(session);
+        session.set_status(status);
+        true
+    }
+
+    fn is_finished(&self, session: &Session) -> bool {
+        self.finished_at
+            .is_some_and(|words| self.on_finish.is_over(session, words))
+    }
+
+    fn on_end(&mut self, _session: &mut Session, out: &mut dyn Write, _quit: bool) {
+        if self.finished_at.is_some() {
+            let done = self.watch.done();
+            let mut chars = done.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string());
+            writeln!(
+                out,
+                "{}{}. Here's your score:",
+                first.unwrap_or_default(),
+                chars.as_str()
+            )
+            .unwrap();
+        }
+    }
+}
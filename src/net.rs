@@ -0,0 +1,345 @@
+use crate::config::Config;
+use crate::language;
+use crate::score::Score;
+use crate::words::{Difficulty, StrokeCount};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 7878;
+/// How long the host waits for a player that connected to say who they are.
+const JOIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Everything the players need to type the same words as the host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setup {
+    pub seed: u64,
+    pub time: u32,
+    pub language: String,
+    pub difficulty: Difficulty,
+    pub punctuation: bool,
+    pub numbers: bool,
+    pub players: Vec<String>,
+}
+
+impl Setup {
+    /// Makes `config` pick the words of the race, leaving out what the player
+    /// chose for themselves, like code to type or a pace to follow.
+    pub fn configure(&self, config: &mut Config) -> Result<(), String> {
+        config.language = language::find(&self.language)
+            .ok_or_else(|| format!("language '{}' is not available", self.language))?;
+        config.code = None;
+        config.text = None;
+        config.pace = None;
+        config.difficulty = self.difficulty.clone();
+        config.punctuation = self.punctuation;
+        config.numbers = self.numbers;
+        config.seed = Some(self.seed);
+        Ok(())
+    }
+}
+
+/// A line of the race protocol. Player names can't contain whitespace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// A player asks the host to take part.
+    Join { name: String },
+    /// The host turned the player down.
+    Refuse { reason: String },
+    /// The race starts for everyone.
+    Start(Setup),
+    /// Correct keystrokes a player made so far.
+    Progress { name: String, strokes: u32 },
+    /// A player's score once their game is over.
+    Done { name: String, score: Score },
+}
+
+impl Message {
+    /// The player the message is about, if any.
+    pub fn player(&self) -> Option<&str> {
+        match self {
+            Message::Join { name }
+            | Message::Progress { name, .. }
+            | Message::Done { name, .. } => Some(name),
+            Message::Refuse { .. } | Message::Start(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Join { name } => write!(f, "join {}", name),
+            Message::Refuse { reason } => write!(f, "refuse {}", reason),
+            Message::Start(setup) => write!(
+                f,
+                "start {} {} {} {} {} {} {} {} {} {}",
+                setup.seed,
+                setup.time,
+                setup.language,
+                setup.difficulty.ranks.start,
+                setup.difficulty.ranks.end,
+                setup.difficulty.lengths.start(),
+                setup.difficulty.lengths.end(),
+                setup.punctuation as u8,
+                setup.numbers as u8,
+                setup.players.join(" ")
+            ),
+            Message::Progress { name, strokes } => write!(f, "progress {} {}", name, strokes),
+            Message::Done { name, score } => write!(
+                f,
+                "done {} {} {} {} {} {}",
+                name, score.wpm, score.accuracy, score.correct, score.incorrect, score.time
+            ),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid message: {}", s);
+        let mut fields = s.split_whitespace();
+        let kind = fields.next().ok_or_else(invalid)?;
+        let mut next = || fields.next().ok_or_else(invalid);
+        let message = match kind {
+            "join" => Message::Join {
+                name: next()?.to_string(),
+            },
+            "refuse" => Message::Refuse {
+                reason: s["refuse".len()..].trim().to_string(),
+            },
+            "start" => {
+                let seed = parse(next()?)?;
+                let time = parse(next()?)?;
+                let language = next()?.to_string();
+                let ranks = parse(next()?)?..parse(next()?)?;
+                let lengths = parse(next()?)?..=parse(next()?)?;
+                let punctuation = next()? == "1";
+                let numbers = next()? == "1";
+                Message::Start(Setup {
+                    seed,
+                    time,
+                    language,
+                    difficulty: Difficulty { ranks, lengths },
+                    punctuation,
+                    numbers,
+                    players: fields.map(String::from).collect(),
+                })
+            }
+            "progress" => Message::Progress {
+                name: next()?.to_string(),
+                strokes: parse(next()?)?,
+            },
+            "done" => Message::Done {
+                name: next()?.to_string(),
+                score: Score {
+                    wpm: parse(next()?)?,
+                    accuracy: parse(next()?)?,
+                    correct: parse(next()?)?,
+                    incorrect: parse(next()?)?,
+                    time: parse(next()?)?,
+                    letters: StrokeCount::default(),
+                    symbols: StrokeCount::default(),
+                },
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(message)
+    }
+}
+
+fn parse<T: FromStr>(field: &str) -> Result<T, String> {
+    field
+        .parse()
+        .map_err(|_| format!("invalid field: {}", field))
+}
+
+/// Players gathered by the host before the race starts.
+pub struct Lobby {
+    listener: TcpListener,
+    players: Vec<String>,
+    streams: Vec<TcpStream>,
+    sender: Sender<Message>,
+    incoming: Receiver<Message>,
+}
+
+impl Lobby {
+    /// Listens for players on `port` of every address, with the host as the
+    /// first player.
+    pub fn open(port: u16, name: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        let (sender, incoming) = mpsc::channel();
+        Ok(Self {
+            listener,
+            players: vec![name.to_string()],
+            streams: Vec::new(),
+            sender,
+            incoming,
+        })
+    }
+
+    pub fn port(&self) -> io::Result<u16> {
+        Ok(self.listener.local_addr()?.port())
+    }
+
+    pub fn players(&self) -> &[String] {
+        &self.players
+    }
+
+    /// Lets in the players that connected since the last call. Returns their names.
+    pub fn accept(&mut self) -> io::Result<Vec<String>> {
+        let mut joined = Vec::new();
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(joined),
+                Err(err) => return Err(err),
+            };
+            // A player that fails to join is just left out.
+            if let Ok(name) = self.admit(stream) {
+                joined.push(name);
+            }
+        }
+    }
+
+    fn admit(&mut self, mut stream: TcpStream) -> io::Result<String> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(JOIN_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let name = match line.parse() {
+            Ok(Message::Join { name }) => name,
+            _ => return Err(io::ErrorKind::InvalidData.into()),
+        };
+        if self.players.contains(&name) {
+            let reason = format!("{} is already playing", name);
+            writeln!(stream, "{}", Message::Refuse { reason })?;
+            return Err(io::ErrorKind::AlreadyExists.into());
+        }
+        stream.set_read_timeout(None)?;
+        spawn_reader(reader, self.sender.clone());
+        self.players.push(name.clone());
+        self.streams.push(stream);
+        Ok(name)
+    }
+
+    /// Starts the race for everyone, filling in the players of `setup`.
+    pub fn start(self, mut setup: Setup) -> (Peer, Setup) {
+        setup.players = self.players;
+        let mut peer = Peer {
+            name: setup.players[0].clone(),
+            streams: self.streams,
+            incoming: self.incoming,
+            relay: true,
+        };
+        peer.send(&Message::Start(setup.clone()));
+        (peer, setup)
+    }
+}
+
+/// Joins the race hosted at `address`, waiting for it to start. The port may be
+/// left out for the default one.
+pub fn join(address: &str, name: &str) -> io::Result<(Peer, Setup)> {
+    let mut stream = match address.to_socket_addrs() {
+        Ok(addresses) => TcpStream::connect(&addresses.collect::<Vec<_>>()[..])?,
+        Err(_) => TcpStream::connect((address, DEFAULT_PORT))?,
+    };
+    stream.set_nodelay(true)?;
+    writeln!(
+        stream,
+        "{}",
+        Message::Join {
+            name: name.to_string()
+        }
+    )?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the host left before the race started",
+            ));
+        }
+        match line.parse() {
+            Ok(Message::Start(setup)) => {
+                let (sender, incoming) = mpsc::channel();
+                spawn_reader(reader, sender);
+                let peer = Peer {
+                    name: name.to_string(),
+                    streams: vec![stream],
+                    incoming,
+                    relay: false,
+                };
+                return Ok((peer, setup));
+            }
+            Ok(Message::Refuse { reason }) => {
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, reason))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Passes the messages read from a player on, until they leave.
+fn spawn_reader(reader: BufReader<TcpStream>, sender: Sender<Message>) {
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let message = match line {
+                Ok(line) => line.parse(),
+                Err(_) => break,
+            };
+            if let Ok(message) = message {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// One player's end of a race. The host is connected to every other player and
+/// passes on what each of them sends, the others only to the host.
+pub struct Peer {
+    name: String,
+    streams: Vec<TcpStream>,
+    incoming: Receiver<Message>,
+    relay: bool,
+}
+
+impl Peer {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sends to everyone connected, dropping the players that left.
+    pub fn send(&mut self, message: &Message) {
+        self.streams
+            .retain_mut(|stream| writeln!(stream, "{}", message).is_ok());
+    }
+
+    /// Messages about the other players received since the last call.
+    pub fn receive(&mut self) -> Vec<Message> {
+        let messages: Vec<Message> = self.incoming.try_iter().collect();
+        if self.relay {
+            for message in &messages {
+                self.send(message);
+            }
+        }
+        messages
+            .into_iter()
+            .filter(|message| message.player() != Some(&self.name))
+            .collect()
+    }
+}
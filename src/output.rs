@@ -0,0 +1,244 @@
+use crate::mode::MAX_PROGRESS_LINES;
+use std::collections::VecDeque;
+use std::env;
+use std::io::{self, Read, Write};
+use std::os::unix::process::ExitStatusExt;
+use std::path::PathBuf;
+use std::process::{self, ExitStatus};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const FINISH_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// What to do with the standard error of a command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stderr {
+    Interleave,
+    Separate,
+    Discard,
+}
+
+impl FromStr for Stderr {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "interleave" => Ok(Stderr::Interleave),
+            "separate" => Ok(Stderr::Separate),
+            "discard" => Ok(Stderr::Discard),
+            _ => Err(format!("unknown stderr handling: {}", s)),
+        }
+    }
+}
+
+/// Where the output of a command goes once it is done.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dump {
+    Print,
+    Pager,
+    File(PathBuf),
+}
+
+/// Everything read from a child, with its last lines kept apart so that showing
+/// them doesn't take going through the whole output.
+#[derive(Default)]
+struct Captured {
+    bytes: Vec<u8>,
+    /// Last complete non-empty lines, as they would appear on a terminal.
+    lines: VecDeque<String>,
+    /// The line being written, from its last carriage return on.
+    partial: Vec<u8>,
+}
+
+impl Captured {
+    fn push(&mut self, chunk: &[u8]) {
+        self.bytes.extend_from_slice(chunk);
+        let mut pieces = chunk.split(|&byte| byte == b'\n').peekable();
+        while let Some(piece) = pieces.next() {
+            self.partial.extend_from_slice(piece);
+            if pieces.peek().is_none() {
+                break;
+            }
+            let line = terminal_line(&self.partial);
+            self.partial.clear();
+            if !line.trim().is_empty() {
+                self.lines.push_back(line);
+                if self.lines.len() > MAX_PROGRESS_LINES {
+                    self.lines.pop_front();
+                }
+            }
+        }
+        // Whatever came before a carriage return is overwritten on a terminal.
+        let end = self.partial.len().saturating_sub(1);
+        if let Some(i) = self.partial[..end].iter().rposition(|&byte| byte == b'\r') {
+            self.partial.drain(..=i);
+        }
+    }
+}
+
+/// Collects the output of a child process on background threads, so it can be
+/// shown while the child is still running.
+pub struct OutputReader {
+    captured: Arc<Mutex<Captured>>,
+    readers: Vec<thread::JoinHandle<()>>,
+}
+
+impl Default for OutputReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputReader {
+    pub fn new() -> Self {
+        Self {
+            captured: Arc::new(Mutex::new(Captured::default())),
+            readers: Vec::new(),
+        }
+    }
+
+    /// Reads `source` until EOF, appending everything to the shared buffer.
+    pub fn attach(&mut self, mut source: impl Read + Send + 'static) {
+        let captured = self.captured.clone();
+        self.readers.push(thread::spawn(move || {
+            let mut chunk = [0; 4096];
+            while let Ok(n) = source.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                captured.lock().unwrap().push(&chunk[..n]);
+            }
+        }));
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.captured.lock().unwrap().bytes.len()
+    }
+
+    /// Last `n` non-empty lines, up to `MAX_PROGRESS_LINES`, as they would appear
+    /// on a terminal, without escape sequences and cut to `width` columns.
+    pub fn tail(&self, n: usize, width: usize) -> Vec<String> {
+        let captured = self.captured.lock().unwrap();
+        let partial = terminal_line(&captured.partial);
+        let mut lines: Vec<String> = captured
+            .lines
+            .iter()
+            .chain(Some(&partial))
+            .rev()
+            .filter(|line| !line.trim().is_empty())
+            .take(n)
+            .map(|line| line.chars().take(width).collect())
+            .collect();
+        lines.reverse();
+        lines
+    }
+
+    /// Gives the readers a moment to reach EOF and returns everything read so far.
+    /// Processes left running by the command can hold the output open, so the
+    /// readers aren't waited for indefinitely.
+    pub fn finish(self) -> Vec<u8> {
+        let deadline = Instant::now() + FINISH_TIMEOUT;
+        while Instant::now() < deadline && self.readers.iter().any(|r| !r.is_finished()) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let captured = self.captured.lock().unwrap();
+        captured.bytes.clone()
+    }
+}
+
+/// Shows `text` in `$PAGER`, falling back to `less -R`.
+pub fn page(text: &[u8]) -> io::Result<()> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+    let mut child = process::Command::new("sh")
+        .arg("-c")
+        .arg(&pager)
+        .stdin(process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything, that's fine.
+        stdin.write_all(text).ok();
+    }
+    child.wait()?;
+    Ok(())
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{}B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1}KB", bytes as f32 / 1024.0)
+    } else {
+        format!("{:.1}MB", bytes as f32 / 1024.0 / 1024.0)
+    }
+}
+
+/// Exit code of a command that couldn't be started, like a shell reports for a
+/// missing command.
+pub const NOT_STARTED: i32 = 127;
+
+/// Exit code following the shell convention of 128 + signal number for killed processes.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(signal)) => 128 + signal,
+        (None, None) => 1,
+    }
+}
+
+pub fn describe_status(status: ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(0), _) => "finished successfully".to_string(),
+        (Some(code), _) => format!("failed with exit status {}", code),
+        (None, Some(signal)) => format!("was killed by signal {}", signal_name(signal)),
+        (None, None) => "finished".to_string(),
+    }
+}
+
+fn signal_name(signal: i32) -> String {
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        6 => "SIGABRT",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        15 => "SIGTERM",
+        _ => return signal.to_string(),
+    };
+    format!("{} ({})", signal, name)
+}
+
+/// A line of output as a terminal would show it, after carriage returns.
+fn terminal_line(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches('\r');
+    strip_escapes(line.rsplit('\r').next().unwrap_or(""))
+}
+
+/// Removes ANSI escape sequences and other control characters.
+fn strip_escapes(line: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{001b}' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            } else {
+                chars.next();
+            }
+        } else if c == '\t' {
+            stripped.push(' ');
+        } else if !c.is_control() {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
@@ -0,0 +1,88 @@
+use crate::mode::Key;
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::io::FromRawFd;
+use std::os::unix::process::CommandExt;
+use std::process;
+use std::ptr;
+use termion::terminal_size;
+
+/// A pseudo-terminal the command runs in, so it behaves (and colors its output)
+/// as it would when started from a shell.
+pub struct Pty {
+    master: File,
+}
+
+impl Pty {
+    /// Opens a pseudo-terminal sized like ours and makes it `command`'s stdio and
+    /// controlling terminal.
+    pub fn attach(command: &mut process::Command) -> io::Result<Self> {
+        let (cols, rows) = terminal_size().unwrap_or((80, 24));
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        let mut master = 0;
+        let mut slave = 0;
+        let result =
+            unsafe { libc::openpty(&mut master, &mut slave, ptr::null_mut(), ptr::null(), &size) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Only the copies made for the command's stdio are meant to outlive exec;
+        // a master left open in the command would keep it from ever seeing EOF.
+        for fd in [master, slave] {
+            if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+                let err = io::Error::last_os_error();
+                unsafe {
+                    libc::close(master);
+                    libc::close(slave);
+                }
+                return Err(err);
+            }
+        }
+
+        let (master, slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+        command
+            .stdin(slave.try_clone()?)
+            .stdout(slave.try_clone()?)
+            .stderr(slave);
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        Ok(Self { master })
+    }
+
+    pub fn reader(&self) -> io::Result<File> {
+        self.master.try_clone()
+    }
+
+    /// Forwards a key press to the command as a terminal would encode it.
+    pub fn send_key(&mut self, key: Key) -> io::Result<()> {
+        let bytes = match key {
+            Key::Char('\n') => vec![b'\r'],
+            Key::Char(c) => c.to_string().into_bytes(),
+            Key::Ctrl(c) => vec![(c as u8) & 0x1f],
+            Key::Alt(c) => format!("\u{001b}{}", c).into_bytes(),
+            Key::Backspace => vec![0x7f],
+            Key::Esc => vec![0x1b],
+            Key::Up => b"\x1b[A".to_vec(),
+            Key::Down => b"\x1b[B".to_vec(),
+            Key::Right => b"\x1b[C".to_vec(),
+            Key::Left => b"\x1b[D".to_vec(),
+            Key::Home => b"\x1b[H".to_vec(),
+            Key::End => b"\x1b[F".to_vec(),
+            Key::Delete => b"\x1b[3~".to_vec(),
+            _ => return Ok(()),
+        };
+        self.master.write_all(&bytes)
+    }
+}
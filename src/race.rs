@@ -0,0 +1,86 @@
+use crate::score::Score;
+use std::cmp::Ordering;
+use unicode_width::UnicodeWidthStr;
+
+pub mod mode;
+
+const HEADER: [&str; 5] = ["player", "wpm", "accuracy", "correct", "incorrect"];
+
+/// Scores of the players of a race, to compare once everyone has played.
+#[derive(Debug, Default)]
+pub struct Standings {
+    players: Vec<(String, Score)>,
+}
+
+impl Standings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: &str, score: Score) {
+        self.players.push((name.to_string(), score));
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Players from the fastest to the slowest, ties going to the more accurate.
+    pub fn ranked(&self) -> Vec<&(String, Score)> {
+        let mut ranked: Vec<_> = self.players.iter().collect();
+        ranked.sort_by(|(_, a), (_, b)| {
+            b.wpm.partial_cmp(&a.wpm).unwrap_or(Ordering::Equal).then(
+                b.accuracy
+                    .partial_cmp(&a.accuracy)
+                    .unwrap_or(Ordering::Equal),
+            )
+        });
+        ranked
+    }
+
+    /// A table of the ranked players, one line each.
+    pub fn table(&self) -> String {
+        let rows: Vec<[String; 5]> = self
+            .ranked()
+            .into_iter()
+            .map(|(name, score)| {
+                [
+                    name.clone(),
+                    format!("{:.0}", score.wpm),
+                    format!("{:.2}%", score.accuracy),
+                    score.correct.to_string(),
+                    score.incorrect.to_string(),
+                ]
+            })
+            .collect();
+        let mut widths = HEADER.map(UnicodeWidthStr::width);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+
+        let header = HEADER.map(String::from);
+        let mut table = String::new();
+        for (rank, row) in std::iter::once(&header).chain(&rows).enumerate() {
+            let rank = if rank == 0 {
+                String::new()
+            } else {
+                format!("{}.", rank)
+            };
+            // Names are aligned to the left and numbers to the right.
+            let name = &row[0];
+            let padding = widths[0] - name.width();
+            table.push_str(&format!("{:>3}  {}{}", rank, name, " ".repeat(padding)));
+            for (width, cell) in widths.iter().zip(row).skip(1) {
+                table.push_str(&format!("  {:>width$}", cell, width = width));
+            }
+            table.push('\n');
+        }
+        table
+    }
+}
@@ -0,0 +1,162 @@
+use super::Standings;
+use crate::mode::{Mode, Progress, Session, MAX_PROGRESS_LINES, TICK};
+use crate::net::{Message, Peer, Setup};
+use std::io::{self, Write};
+use std::mem;
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+const BAR_WIDTH: usize = 40;
+/// How long a finished race waits for the scores of the other players.
+const RESULTS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A time mode game raced against other players over the network, starting for
+/// everyone at once, with their progress shown above the words.
+pub struct RaceMode {
+    time: u32,
+    peer: Peer,
+    /// Correct keystrokes of the other players, as last heard.
+    rivals: Vec<(String, u32)>,
+    sent: u32,
+    standings: Standings,
+    last_frame: u32,
+}
+
+impl RaceMode {
+    pub fn new(peer: Peer, setup: &Setup) -> Self {
+        let rivals = setup
+            .players
+            .iter()
+            .filter(|name| *name != peer.name())
+            .map(|name| (name.clone(), 0))
+            .collect();
+        Self {
+            time: setup.time,
+            peer,
+            rivals,
+            sent: 0,
+            standings: Standings::new(),
+            last_frame: 0,
+        }
+    }
+
+    /// Returns whether any of the other players made progress.
+    fn receive(&mut self) -> bool {
+        let mut progress = false;
+        for message in self.peer.receive() {
+            match message {
+                Message::Progress { name, strokes } => {
+                    if let Some(rival) = self.rivals.iter_mut().find(|(rival, _)| *rival == name) {
+                        rival.1 = strokes;
+                        progress = true;
+                    }
+                }
+                Message::Done { name, score } => self.standings.add(&name, score),
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    fn update_progress(&self, session: &mut Session) {
+        let strokes = session.word_queue.correct_stroke_count();
+        let leader = self
+            .rivals
+            .iter()
+            .map(|(_, strokes)| *strokes)
+            .chain(Some(strokes))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let minutes = session.timer.elapsed().as_secs_f32() / 60.0;
+        let name_width = self.rivals.iter().map(|(name, _)| name.width()).max();
+
+        let mut lines = Vec::new();
+        for (name, strokes) in self.rivals.iter().take(MAX_PROGRESS_LINES) {
+            let filled = BAR_WIDTH * *strokes as usize / leader as usize;
+            let wpm = if minutes > 0.0 {
+                *strokes as f32 / 5.0 / minutes
+            } else {
+                0.0
+            };
+            let line = format!(
+                "{}{}  {}{}  {:>3.0}wpm",
+                name,
+                " ".repeat(name_width.unwrap_or(0) - name.width()),
+                "█".repeat(filled),
+                "░".repeat(BAR_WIDTH - filled),
+                wpm
+            );
+            lines.push(line);
+        }
+        let place = 1 + self.rivals.iter().filter(|(_, s)| *s > strokes).count();
+        let left = session.timer.limit().saturating_sub(session.timer.passed());
+        if let Some(progress) = &mut session.progress {
+            progress.lines = lines;
+            progress.status = format!(
+                "{}s left  place {} of {}",
+                left,
+                place,
+                self.rivals.len() + 1
+            );
+        }
+    }
+}
+
+impl Mode for RaceMode {
+    type Outcome = Standings;
+
+    fn on_start(&mut self, session: &mut Session) -> io::Result<()> {
+        session.timer.set(self.time);
+        session.timer.start();
+        session.progress = Some(Progress::new(self.rivals.len()));
+        self.update_progress(session);
+        Ok(())
+    }
+
+    fn on_tick(&mut self, session: &mut Session) -> bool {
+        let strokes = session.word_queue.correct_stroke_count();
+        let mut changed = self.receive();
+        if strokes != self.sent {
+            self.sent = strokes;
+            self.peer.send(&Message::Progress {
+                name: self.peer.name().to_string(),
+                strokes,
+            });
+            changed = true;
+        }
+
+        let frame = session.timer.passed();
+        if !changed && frame == self.last_frame {
+            return false;
+        }
+        self.last_frame = frame;
+        self.update_progress(session);
+        true
+    }
+
+    fn is_finished(&self, session: &Session) -> bool {
+        session.timer.is_limit()
+    }
+
+    /// Waits a little for the scores of the other players, who finish at about
+    /// the same time.
+    fn on_end(&mut self, session: &mut Session, _out: &mut dyn Write, _quit: bool) -> Standings {
+        let name = self.peer.name().to_string();
+        let score = session.score();
+        self.peer.send(&Message::Done {
+            name: name.clone(),
+            score,
+        });
+        self.standings.add(&name, score);
+
+        let started = session.clock.now();
+        while self.standings.len() <= self.rivals.len()
+            && session.clock.now() - started < RESULTS_TIMEOUT
+        {
+            self.receive();
+            session.clock.sleep(TICK);
+        }
+        mem::take(&mut self.standings)
+    }
+}
@@ -0,0 +1,105 @@
+use crate::words::{LiveWord, Typed, WordQueue};
+use std::cmp::Ordering;
+use termion::style;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Copy)]
+enum Color {
+    Reset,
+    Green,
+    Red,
+    /// Background of the ghost caret.
+    YellowBackground,
+    DefaultBackground,
+}
+
+impl Color {
+    pub fn value(&self) -> &str {
+        match self {
+            Color::Reset => "\u{001b}[0m",
+            Color::Green => "\u{001b}[32m",
+            Color::Red => "\u{001b}[31m",
+            Color::YellowBackground => "\u{001b}[43m",
+            Color::DefaultBackground => "\u{001b}[49m",
+        }
+    }
+}
+
+/// The rows of `queue` in color: the words done in green or red, the current one
+/// grapheme by grapheme, and the words to come plain. `ghost` is the row and
+/// column of the ghost caret, if it is on screen.
+pub fn rows(queue: &WordQueue, highlight: bool, ghost: Option<(usize, usize)>) -> Vec<String> {
+    queue
+        .rows()
+        .iter()
+        .enumerate()
+        .map(|(i, words)| {
+            let mut row = Row {
+                text: String::new(),
+                col: 0,
+                ghost: ghost.filter(|(row, _)| *row == i).map(|(_, col)| col),
+            };
+            for (index, word) in words.iter().enumerate() {
+                match (i, index.cmp(&queue.current_index())) {
+                    (0, Ordering::Less) => {
+                        let color = if word.is_correct() {
+                            Color::Green
+                        } else {
+                            Color::Red
+                        };
+                        row.push(&word.display(), Some(color));
+                    }
+                    (0, Ordering::Equal) => row.push_current(word, highlight),
+                    _ => row.push(&word.display(), None),
+                }
+                row.push(&word.separator().display(), None);
+            }
+            row.text
+        })
+        .collect()
+}
+
+/// A row being rendered, with the column reached so far.
+struct Row {
+    text: String,
+    col: usize,
+    ghost: Option<usize>,
+}
+
+impl Row {
+    /// Appends `text` in `color`, marking the character the ghost caret is on.
+    fn push(&mut self, text: &str, color: Option<Color>) {
+        if let Some(color) = color {
+            self.text.push_str(color.value());
+        }
+        for grapheme in text.graphemes(true) {
+            if self.ghost == Some(self.col) {
+                self.text.push_str(Color::YellowBackground.value());
+                self.text.push_str(grapheme);
+                self.text.push_str(Color::DefaultBackground.value());
+            } else {
+                self.text.push_str(grapheme);
+            }
+            self.col += grapheme.width();
+        }
+        if color.is_some() {
+            self.text.push_str(Color::Reset.value());
+        }
+    }
+
+    fn push_current(&mut self, word: &LiveWord, highlight: bool) {
+        for (grapheme, typed) in word.graphemes() {
+            match typed {
+                Typed::Correct => self.push(&grapheme, Some(Color::Green)),
+                Typed::Incorrect => self.push(&grapheme, Some(Color::Red)),
+                Typed::Next if highlight => {
+                    self.text.push_str(style::Invert.as_ref());
+                    self.push(&grapheme, None);
+                    self.text.push_str(style::NoInvert.as_ref());
+                }
+                Typed::Next | Typed::Pending => self.push(&grapheme, None),
+            }
+        }
+    }
+}
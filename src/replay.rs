@@ -0,0 +1,70 @@
+use crate::config;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The pace of a game: when each correct keystroke was made, in milliseconds
+/// since the first key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub wpm: f32,
+    pub strokes: Vec<u32>,
+}
+
+impl Replay {
+    /// Reads a replay saved as its speed followed by one keystroke per line.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not a replay", path.display()),
+            )
+        };
+        let mut lines = text.lines();
+        let wpm = f32::from_str(lines.next().unwrap_or("")).map_err(|_| invalid())?;
+        let strokes = lines
+            .map(u32::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+        Ok(Self { wpm, strokes })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = format!("{}\n", self.wpm);
+        for stroke in &self.strokes {
+            text.push_str(&format!("{}\n", stroke));
+        }
+        fs::write(path, text)
+    }
+}
+
+/// How fast the ghost caret moves through the words.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pace {
+    Wpm(f32),
+    Replay(Replay),
+}
+
+impl Pace {
+    /// Keystrokes the ghost has made `elapsed` into the game.
+    pub fn strokes_at(&self, elapsed: Duration) -> usize {
+        match self {
+            Pace::Wpm(wpm) => (elapsed.as_secs_f32() * wpm * 5.0 / 60.0) as usize,
+            Pace::Replay(replay) => {
+                let ms = elapsed.as_millis() as u32;
+                replay.strokes.partition_point(|&stroke| stroke <= ms)
+            }
+        }
+    }
+}
+
+/// Where the personal best of a mode, e.g. "time60", is kept.
+pub fn best_path(mode: &str) -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(format!("best-{}.replay", mode)))
+}
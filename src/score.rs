@@ -0,0 +1,42 @@
+use crate::words::{StrokeCount, WordQueue};
+
+/// Results of a game, worked out from the words typed and the seconds played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    pub correct: u32,
+    pub incorrect: u32,
+    /// Percentage of words typed correctly.
+    pub accuracy: f32,
+    /// Words per minute, counting five correct keystrokes as a word.
+    pub wpm: f32,
+    pub time: u32,
+    pub letters: StrokeCount,
+    pub symbols: StrokeCount,
+}
+
+impl Score {
+    pub fn new(word_queue: &WordQueue, time: u32) -> Self {
+        let (correct, incorrect) = word_queue.words_count();
+        let accuracy = if correct + incorrect == 0 {
+            0.0
+        } else {
+            correct as f32 / (correct + incorrect) as f32 * 100.0
+        };
+        let wpm = if time == 0 {
+            0.0
+        } else {
+            word_queue.correct_stroke_count() as f32 / 5.0 / time as f32 * 60.0
+        };
+        let (letters, symbols) = word_queue.stroke_counts();
+
+        Self {
+            correct,
+            incorrect,
+            accuracy,
+            wpm,
+            time,
+            letters,
+            symbols,
+        }
+    }
+}
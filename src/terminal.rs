@@ -0,0 +1,112 @@
+use crate::mode::Key;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use termion::event;
+use termion::input::{Keys, TermRead};
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::{terminal_size, AsyncReader};
+
+/// Where the game gets the keys it is played with.
+pub trait Input {
+    /// The next key pressed, if any. Doesn't wait for one.
+    fn next_key(&mut self) -> Option<Key>;
+}
+
+/// Where the game is drawn.
+pub trait Output: Write {
+    /// Columns and rows available.
+    fn size(&self) -> (u16, u16);
+    /// Raw mode is on while playing, and off again for the end screen.
+    fn set_raw_mode(&mut self, raw: bool) -> io::Result<()>;
+}
+
+/// Lets games played one after another share an input, e.g. so keys aren't lost
+/// to the reader of a game that is over.
+impl<I: Input> Input for Rc<RefCell<I>> {
+    fn next_key(&mut self) -> Option<Key> {
+        self.borrow_mut().next_key()
+    }
+}
+
+pub struct Stdin {
+    keys: Keys<AsyncReader>,
+}
+
+impl Stdin {
+    pub fn new() -> Self {
+        Self {
+            keys: termion::async_stdin().keys(),
+        }
+    }
+}
+
+impl Default for Stdin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Input for Stdin {
+    fn next_key(&mut self) -> Option<Key> {
+        self.keys.next().and_then(|key| key.ok()).map(Key::from)
+    }
+}
+
+impl From<event::Key> for Key {
+    fn from(key: event::Key) -> Self {
+        match key {
+            event::Key::Char(c) => Key::Char(c),
+            event::Key::Ctrl(c) => Key::Ctrl(c),
+            event::Key::Alt(c) => Key::Alt(c),
+            event::Key::Backspace => Key::Backspace,
+            event::Key::Delete => Key::Delete,
+            event::Key::Esc => Key::Esc,
+            event::Key::Up => Key::Up,
+            event::Key::Down => Key::Down,
+            event::Key::Left => Key::Left,
+            event::Key::Right => Key::Right,
+            event::Key::Home => Key::Home,
+            event::Key::End => Key::End,
+            _ => Key::Other,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Stdout {
+    raw: Option<RawTerminal<io::Stdout>>,
+}
+
+impl Stdout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stdout().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+impl Output for Stdout {
+    fn size(&self) -> (u16, u16) {
+        terminal_size().unwrap_or((80, 24))
+    }
+
+    fn set_raw_mode(&mut self, raw: bool) -> io::Result<()> {
+        if raw {
+            self.raw = Some(io::stdout().into_raw_mode()?);
+        } else if let Some(terminal) = self.raw.take() {
+            // Dropping the terminal restores the previous mode as well, but errors
+            // would go unnoticed.
+            terminal.suspend_raw_mode()?;
+        }
+        Ok(())
+    }
+}
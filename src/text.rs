@@ -0,0 +1,31 @@
+use crate::words::{split_rows, tokenize, Feed, LiveWord};
+use std::collections::VecDeque;
+
+/// Feeds a passage verbatim, wrapped to the row width, starting over once it is typed through.
+pub struct TextFeed {
+    text: String,
+    rows: VecDeque<Vec<LiveWord>>,
+}
+
+impl TextFeed {
+    pub fn new(text: &str) -> Self {
+        let text = text
+            .lines()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect();
+        Self {
+            text,
+            rows: VecDeque::new(),
+        }
+    }
+}
+
+impl Feed for TextFeed {
+    fn next_row(&mut self, fit_row_into_len: u8) -> Vec<LiveWord> {
+        if self.rows.is_empty() {
+            self.rows
+                .extend(split_rows(tokenize(&self.text), fit_row_into_len as usize));
+        }
+        self.rows.pop_front().unwrap()
+    }
+}
@@ -1,18 +1,80 @@
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Source of time for the game, so it can be played against a fake one in tests.
+pub trait Clock {
+    /// Time passed since some fixed point.
+    fn now(&self) -> Duration;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves when slept on or advanced. Clones share the time.
+#[derive(Debug, Clone, Default)]
+pub struct FakeClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
 
 pub struct Timer {
-    running: bool,
-    passed: Arc<Mutex<u32>>,
+    clock: Rc<dyn Clock>,
+    started: Option<Duration>,
     limit: u32,
 }
 
 impl Timer {
-    pub fn new(limit: u32) -> Self {
+    pub fn new(limit: u32, clock: Rc<dyn Clock>) -> Self {
         Self {
-            running: false,
-            passed: Arc::new(Mutex::new(0)),
+            clock,
+            started: None,
             limit,
         }
     }
@@ -21,24 +83,34 @@ impl Timer {
         self.limit = limit;
     }
 
+    /// Moves the limit by `seconds` while the game is played, never below zero.
+    pub fn adjust(&mut self, seconds: i32) {
+        self.limit = (self.limit as i64 + seconds as i64).max(0) as u32;
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
     pub fn running(&self) -> bool {
-        self.running
+        self.started.is_some()
     }
 
+    /// Whole seconds since the timer was started.
     pub fn passed(&self) -> u32 {
-        *self.passed.clone().lock().unwrap()
+        self.elapsed().as_secs() as u32
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started
+            .map_or(Duration::ZERO, |started| self.clock.now() - started)
     }
 
     pub fn is_limit(&self) -> bool {
-        self.limit <= *self.passed.clone().lock().unwrap()
+        self.limit <= self.passed()
     }
 
     pub fn start(&mut self) {
-        self.running = true;
-        let passed = self.passed.clone();
-        thread::spawn(move || loop {
-            thread::sleep(Duration::from_millis(1000));
-            *passed.lock().unwrap() += 1;
-        });
+        self.started = Some(self.clock.now());
     }
 }
@@ -0,0 +1,212 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Tables of the IPv4 and IPv6 TCP sockets in our network namespace.
+const TCP_TABLES: [&str; 2] = ["/proc/net/tcp", "/proc/net/tcp6"];
+/// State of a listening socket in the tables.
+const TCP_LISTEN: &str = "0A";
+
+/// Something outside the game to play until, checked on every tick.
+pub trait Watch {
+    /// What is being waited for, e.g. "process 42 (make) to exit".
+    fn waiting_for(&self) -> String;
+    /// How it ended, e.g. "process 42 (make) has exited".
+    fn done(&self) -> String;
+    fn is_done(&mut self) -> bool;
+}
+
+/// A process that isn't ours, found through `/proc`.
+pub struct Process {
+    pid: u32,
+    name: String,
+}
+
+impl Process {
+    pub fn attach(pid: u32) -> io::Result<Self> {
+        let process = Self {
+            pid,
+            name: String::new(),
+        };
+        if !process.is_running() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no process with pid {}", pid),
+            ));
+        }
+        let name = fs::read_to_string(process.path().join("comm")).unwrap_or_default();
+        Ok(Self {
+            name: name.trim_end().to_string(),
+            ..process
+        })
+    }
+
+    fn path(&self) -> PathBuf {
+        PathBuf::from(format!("/proc/{}", self.pid))
+    }
+
+    /// A process that exited but wasn't reaped by its parent yet still has its
+    /// `/proc` entry, in the zombie state.
+    fn is_running(&self) -> bool {
+        match fs::read_to_string(self.path().join("stat")) {
+            // The state follows the name, which is in parentheses and may contain anything.
+            Ok(stat) => stat
+                .rsplit(')')
+                .next()
+                .and_then(|rest| rest.split_whitespace().next())
+                .is_some_and(|state| state != "Z" && state != "X"),
+            Err(_) => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        if self.name.is_empty() {
+            format!("process {}", self.pid)
+        } else {
+            format!("process {} ({})", self.pid, self.name)
+        }
+    }
+}
+
+impl Watch for Process {
+    fn waiting_for(&self) -> String {
+        format!("{} to exit", self.describe())
+    }
+
+    fn done(&self) -> String {
+        format!("{} has exited", self.describe())
+    }
+
+    fn is_done(&mut self) -> bool {
+        !self.is_running()
+    }
+}
+
+/// A file that doesn't exist yet, like a build artifact.
+pub struct Appears {
+    path: PathBuf,
+}
+
+impl Appears {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Watch for Appears {
+    fn waiting_for(&self) -> String {
+        format!("{} to appear", self.path.display())
+    }
+
+    fn done(&self) -> String {
+        format!("{} has appeared", self.path.display())
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.path.exists()
+    }
+}
+
+/// A file that exists for as long as something is going on, like a lockfile.
+pub struct Disappears {
+    path: PathBuf,
+}
+
+impl Disappears {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        if !path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} doesn't exist", path.display()),
+            ));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Watch for Disappears {
+    fn waiting_for(&self) -> String {
+        format!("{} to disappear", self.path.display())
+    }
+
+    fn done(&self) -> String {
+        format!("{} has disappeared", self.path.display())
+    }
+
+    fn is_done(&mut self) -> bool {
+        !self.path.exists()
+    }
+}
+
+/// A local TCP port nothing listens on yet, like a dev server starting up. Found
+/// through `/proc` rather than by connecting, which the server would notice.
+pub struct Listening {
+    port: u16,
+}
+
+impl Listening {
+    pub fn new(port: u16) -> io::Result<Self> {
+        if is_listening(port)? {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("port {} is already listening", port),
+            ));
+        }
+        Ok(Self { port })
+    }
+}
+
+impl Watch for Listening {
+    fn waiting_for(&self) -> String {
+        format!("port {} to listen", self.port)
+    }
+
+    fn done(&self) -> String {
+        format!("port {} is listening", self.port)
+    }
+
+    fn is_done(&mut self) -> bool {
+        is_listening(self.port).unwrap_or(false)
+    }
+}
+
+/// Whether a TCP socket listens on `port`, on any address.
+fn is_listening(port: u16) -> io::Result<bool> {
+    let mut found_table = false;
+    for table in TCP_TABLES.iter() {
+        let text = match fs::read_to_string(table) {
+            Ok(text) => text,
+            // IPv6 may be disabled.
+            Err(_) => continue,
+        };
+        found_table = true;
+        let listening = text.lines().skip(1).any(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let local_port = columns
+                .get(1)
+                .and_then(|address| address.rsplit(':').next())
+                .and_then(|port| u16::from_str_radix(port, 16).ok());
+            local_port == Some(port) && columns.get(3) == Some(&TCP_LISTEN)
+        });
+        if listening {
+            return Ok(true);
+        }
+    }
+    if !found_table {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "can't read the socket tables in /proc/net",
+        ));
+    }
+    Ok(false)
+}
@@ -1,55 +1,275 @@
-use rand::Rng;
-use std::ops::Range;
-use termion::event::Key;
-
-const WORDS: &str = include_str!("ngsl.txt");
-
-#[allow(dead_code)]
-enum Color {
-    Reset,
-    Green,
-    Red,
-    Yellow,
-    White,
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::ops::{Range, RangeInclusive};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns a tab occupies when rendered.
+pub const TAB_WIDTH: usize = 4;
+
+/// Narrows a frequency ordered word list down to a rank range and word lengths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    pub ranks: Range<usize>,
+    pub lengths: RangeInclusive<usize>,
 }
 
-impl Color {
-    pub fn value(&self) -> &str {
-        match self {
-            Color::Reset => "\u{001b}[0m",
-            Color::Green => "\u{001b}[32m",
-            Color::Red => "\u{001b}[31m",
-            Color::Yellow => "\u{001b}[33m",
-            Color::White => "\u{001b}[37m",
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            ranks: 0..usize::MAX,
+            lengths: 1..=usize::MAX,
         }
     }
 }
 
+/// A source of rows for the `WordQueue`.
+pub trait Feed {
+    fn next_row(&mut self, fit_row_into_len: u8) -> Vec<LiveWord>;
+    /// Narrows the words of the rows to come, for feeds that pick them from a list.
+    fn set_difficulty(&mut self, _difficulty: &Difficulty) {}
+}
+
 #[derive(Debug)]
 pub struct WordFeed<'a> {
+    list: &'a str,
     words: Vec<&'a str>,
+    punctuation: bool,
+    numbers: bool,
+    sentence_start: bool,
+    /// Unlike `StdRng`, gives the same numbers for a seed on every version of
+    /// rand, so players with different builds type the same words.
+    rng: ChaCha8Rng,
 }
 
 impl<'a> WordFeed<'a> {
-    pub fn new() -> Self {
-        let words: Vec<&str> = WORDS.lines().collect();
+    pub fn new(list: &'a str, difficulty: &Difficulty) -> Self {
+        WordFeed {
+            list,
+            words: select(list, difficulty),
+            punctuation: false,
+            numbers: false,
+            sentence_start: true,
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    /// Makes the words picked the same for every feed with the same seed, list
+    /// and options.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn set_punctuation(&mut self, punctuation: bool) {
+        self.punctuation = punctuation;
+    }
+
+    pub fn set_numbers(&mut self, numbers: bool) {
+        self.numbers = numbers;
+    }
 
-        WordFeed { words }
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
     }
 
-    pub fn get_random(&self) -> LiveWord {
-        let random_int: u32 = random(0..self.words.len() as u32);
-        let word = &self.words[random_int as usize];
-        LiveWord::new(word.to_string())
+    pub fn get_random(&mut self) -> LiveWord {
+        let index = self.rng.gen_range(0..self.words.len());
+        let mut word = self.words[index].to_string();
+        if self.numbers && self.rng.gen_range(0..100) < 10 {
+            word = self.rng.gen_range(0..10000).to_string();
+        }
+        if self.punctuation {
+            word = self.punctuate(word);
+        }
+        LiveWord::new(word)
+    }
+
+    /// Makes the feed read like prose: capitalizes sentence starts and
+    /// randomly attaches punctuation, quotes and brackets.
+    fn punctuate(&mut self, word: String) -> String {
+        let mut word = if self.sentence_start {
+            capitalize(&word)
+        } else {
+            word
+        };
+        self.sentence_start = false;
+
+        match self.rng.gen_range(0..100) {
+            0..=7 => {
+                word.push('.');
+                self.sentence_start = true;
+            }
+            8..=9 => {
+                word.push('?');
+                self.sentence_start = true;
+            }
+            10 => {
+                word.push('!');
+                self.sentence_start = true;
+            }
+            11..=20 => word.push(','),
+            21 => word.push(';'),
+            22 => word.push(':'),
+            23..=25 => word = format!("\"{}\"", word),
+            26..=27 => word = format!("({})", word),
+            _ => {}
+        }
+
+        word
     }
 }
 
-#[derive(Debug)]
-pub struct LiveWord((String, String), bool);
+impl<'a> Feed for WordFeed<'a> {
+    fn next_row(&mut self, fit_row_into_len: u8) -> Vec<LiveWord> {
+        gen_row(self, fit_row_into_len)
+    }
+
+    /// Keeps the current words if none match.
+    fn set_difficulty(&mut self, difficulty: &Difficulty) {
+        let words = select(self.list, difficulty);
+        if !words.is_empty() {
+            self.words = words;
+        }
+    }
+}
+
+/// Words of a frequency ordered list that match `difficulty`.
+fn select<'a>(list: &'a str, difficulty: &Difficulty) -> Vec<&'a str> {
+    list.lines()
+        .skip(difficulty.ranks.start)
+        .take(difficulty.ranks.end.saturating_sub(difficulty.ranks.start))
+        .filter(|word| difficulty.lengths.contains(&word.graphemes(true).count()))
+        .collect()
+}
+
+/// Whitespace expected after a word, e.g. a space, a line break or a blank line,
+/// along with the whitespace typed in its place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Separator(String, String);
+
+impl Separator {
+    pub fn new(expected: &str) -> Self {
+        Separator(expected.to_string(), String::new())
+    }
+
+    pub fn space() -> Self {
+        Separator::new(" ")
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_started(&self) -> bool {
+        !self.1.is_empty()
+    }
+
+    pub fn is_correct(&self) -> bool {
+        self.0 == self.1
+    }
+
+    /// Returns true once as many keys were typed as the separator expects.
+    fn push(&mut self, c: char) -> bool {
+        self.1.push(c);
+        self.1.chars().count() >= self.0.chars().count()
+    }
+
+    fn pop(&mut self) -> bool {
+        self.1.pop().is_some()
+    }
+
+    /// Expected whitespace as rendered, with line breaks kept visible.
+    pub fn display(&self) -> String {
+        self.0.chars().map(display_whitespace).collect()
+    }
+
+    fn typed_width(&self) -> usize {
+        self.0
+            .chars()
+            .take(self.1.chars().count())
+            .map(display_whitespace)
+            .collect::<String>()
+            .width()
+    }
+}
+
+/// Whether a keystroke was meant to produce a letter (or digit) or a symbol.
+pub enum StrokeClass {
+    Letter,
+    Symbol,
+}
+
+impl StrokeClass {
+    fn of(grapheme: &str) -> Self {
+        if grapheme.chars().all(char::is_alphanumeric) {
+            StrokeClass::Letter
+        } else {
+            StrokeClass::Symbol
+        }
+    }
+}
+
+/// How a grapheme of the expected word stands against what was typed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Typed {
+    Correct,
+    /// The first grapheme typed wrong. The ones after it are `Pending`.
+    Incorrect,
+    /// The grapheme the next keystroke should produce, while the word is right so far.
+    Next,
+    Pending,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StrokeCount {
+    pub correct: u32,
+    pub incorrect: u32,
+}
+
+impl StrokeCount {
+    pub fn accuracy(&self) -> f32 {
+        if self.correct + self.incorrect == 0 {
+            0.0
+        } else {
+            self.correct as f32 / (self.correct + self.incorrect) as f32 * 100.0
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveWord((String, String), bool, Separator);
 
 impl LiveWord {
     pub fn new(expected: String) -> Self {
-        LiveWord((expected, String::new()), false)
+        LiveWord((expected, String::new()), false, Separator::space())
+    }
+
+    pub fn with_separator(expected: String, separator: Separator) -> Self {
+        LiveWord((expected, String::new()), false, separator)
+    }
+
+    pub fn separator(&self) -> &Separator {
+        &self.2
+    }
+
+    /// Types `c` into the separator, returning true once the separator is complete.
+    pub fn push_separator(&mut self, c: char) -> bool {
+        self.2.push(c)
+    }
+
+    pub fn pop_separator(&mut self) -> bool {
+        self.2.pop()
+    }
+
+    /// Expected word as rendered, with tabs expanded to spaces.
+    pub fn display(&self) -> String {
+        self.0 .0.replace('\t', &" ".repeat(TAB_WIDTH))
+    }
+
+    /// Grapheme the next keystroke should produce, if any.
+    pub fn next_expected(&self) -> Option<&str> {
+        let (expected, actual) = &self.0;
+        expected.graphemes(true).nth(actual.graphemes(true).count())
     }
 
     pub fn expected(&self) -> String {
@@ -66,15 +286,25 @@ impl LiveWord {
         self.1
     }
 
+    /// Removes the last grapheme, so a single backspace undoes e.g. a letter with its accent.
     pub fn pop_char(&mut self) -> bool {
-        self.0 .1.pop();
+        let actual = &mut self.0 .1;
+        if let Some((index, _)) = actual.grapheme_indices(true).next_back() {
+            actual.truncate(index);
+        }
         self.1 = self.0 .0 == self.0 .1;
         self.1
     }
 
-    pub fn correct_stroke_count(&self) -> u16 {
+    /// Display width of the expected word in terminal columns.
+    pub fn width(&self) -> usize {
+        self.display().width()
+    }
+
+    pub fn correct_stroke_count(&self) -> u32 {
         let mut count = 0;
-        for (a, e) in self.actual().chars().zip(self.expected().chars()) {
+        let (expected, actual) = &self.0;
+        for (a, e) in actual.graphemes(true).zip(expected.graphemes(true)) {
             if a == e {
                 count += 1
             } else {
@@ -85,71 +315,127 @@ impl LiveWord {
     }
 
     pub fn is_correct(&self) -> bool {
-        self.1
+        self.1 && self.2.is_correct()
     }
 
-    pub fn to_color_string_outcome(&self) -> String {
-        let color = if self.is_correct() {
-            Color::Green.value()
-        } else {
-            Color::Red.value()
-        };
-        format!("{}{}{}", color, self.expected(), Color::Reset.value())
+    /// Graphemes of the expected word as rendered, each with how it was typed.
+    pub fn graphemes(&self) -> Vec<(String, Typed)> {
+        let (expected, actual) = &self.0;
+        let mut typed = actual.graphemes(true);
+        let mut done = false;
+        expected
+            .graphemes(true)
+            .map(|grapheme| {
+                let state = if done {
+                    Typed::Pending
+                } else {
+                    match typed.next() {
+                        Some(typed) if typed == grapheme => Typed::Correct,
+                        Some(_) => {
+                            done = true;
+                            Typed::Incorrect
+                        }
+                        None => {
+                            done = true;
+                            Typed::Next
+                        }
+                    }
+                };
+                (display_grapheme(grapheme), state)
+            })
+            .collect()
     }
 
-    pub fn to_color_string_outcome_detail(&self) -> String {
-        let colors = (
-            Color::Green.value(),
-            Color::Red.value(),
-            Color::Reset.value(),
-        );
-        get_color_string_outcome_detail(&self.expected(), &self.actual(), colors)
+    /// Column of the next expected character, never past the end of the word
+    /// unless the separator is being typed.
+    pub fn caret_offset(&self) -> usize {
+        if self.2.is_started() {
+            return self.width() + self.2.typed_width();
+        }
+        let (expected, actual) = &self.0;
+        let typed = actual.graphemes(true).count();
+        expected
+            .graphemes(true)
+            .take(typed)
+            .map(grapheme_width)
+            .sum()
     }
 }
 
 pub struct WordQueue<'a> {
-    feed: WordFeed<'a>,
-    current_index: u8,
+    feed: Box<dyn Feed + 'a>,
+    current_index: usize,
     fit_row_into_len: u8,
     num_rows: u8,
     rows: Vec<Vec<LiveWord>>,
-    correct_count: u16,
-    incorrect_count: u16,
-    correct_stroke_count: u16,
+    flushed_strokes: usize,
+    completed: Option<LiveWord>,
+    correct_count: u32,
+    incorrect_count: u32,
+    correct_stroke_count: u32,
+    letter_strokes: StrokeCount,
+    symbol_strokes: StrokeCount,
 }
 
 impl<'a> WordQueue<'a> {
-    pub fn new(feed: WordFeed<'a>) -> Self {
+    pub fn new(feed: impl Feed + 'a) -> Self {
         Self {
-            feed,
+            feed: Box::new(feed),
             current_index: 0,
             fit_row_into_len: 60,
             num_rows: 2,
             rows: vec![],
+            flushed_strokes: 0,
+            completed: None,
             correct_count: 0,
             incorrect_count: 0,
             correct_stroke_count: 0,
+            letter_strokes: StrokeCount::default(),
+            symbol_strokes: StrokeCount::default(),
         }
     }
 
+    /// Applies to the rows generated from now on.
+    pub fn set_difficulty(&mut self, difficulty: &Difficulty) {
+        self.feed.set_difficulty(difficulty);
+    }
+
+    /// Fills the rows to type, replacing any there were.
     pub fn init(&mut self) {
+        self.rows.clear();
+        self.current_index = 0;
         for _ in 0..self.num_rows {
-            self.rows.push(self.gen_row());
+            let row = self.gen_row();
+            self.rows.push(row);
         }
     }
 
-    fn gen_row(&self) -> Vec<LiveWord> {
-        gen_row(&self.feed, self.fit_row_into_len)
+    fn gen_row(&mut self) -> Vec<LiveWord> {
+        self.feed.next_row(self.fit_row_into_len)
     }
 
     pub fn get_current_word_ref(&mut self) -> &mut LiveWord {
         self.rows
             .get_mut(0)
             .unwrap()
-            .get_mut(self.current_index as usize)
+            .get_mut(self.current_index)
             .unwrap()
     }
 
+    pub fn current_word(&self) -> &LiveWord {
+        &self.rows[0][self.current_index]
+    }
+
+    /// Rows of words on screen, the first one being typed.
+    pub fn rows(&self) -> &[Vec<LiveWord>] {
+        &self.rows
+    }
+
+    /// Index of the current word within the first row.
+    pub fn current_index(&self) -> usize {
+        self.current_index
+    }
+
     fn move_index(&mut self) {
         if self.get_current_word_ref().is_correct() {
             self.correct_count += 1;
@@ -158,12 +444,14 @@ impl<'a> WordQueue<'a> {
         }
 
         self.correct_stroke_count += self.get_current_word_ref().correct_stroke_count();
+        self.completed = Some(self.get_current_word_ref().clone());
 
-        if let Some(_) = self
+        if self
             .rows
-            .get(0)
+            .first()
             .unwrap()
-            .get(self.current_index as usize + 1)
+            .get(self.current_index + 1)
+            .is_some()
         {
             self.current_index += 1;
         } else {
@@ -171,146 +459,235 @@ impl<'a> WordQueue<'a> {
         }
     }
 
-    pub fn words_count(&self) -> (u16, u16) {
+    /// Column of the next expected character within the first row.
+    pub fn caret_offset(&self) -> u16 {
+        let row = self.rows.first().unwrap();
+        let index = self.current_index;
+        let preceding: usize = row[..index]
+            .iter()
+            .map(|word| word.width() + word.separator().display().width())
+            .sum();
+        (preceding + row[index].caret_offset()) as u16
+    }
+
+    /// The word completed since the last call, if any.
+    pub fn take_completed(&mut self) -> Option<LiveWord> {
+        self.completed.take()
+    }
+
+    pub fn words_count(&self) -> (u32, u32) {
         (self.correct_count, self.incorrect_count)
     }
 
-    pub fn correct_stroke_count(&self) -> u16 {
+    pub fn correct_stroke_count(&self) -> u32 {
         self.correct_stroke_count
     }
 
+    /// Keystroke counts for letters and symbols.
+    pub fn stroke_counts(&self) -> (StrokeCount, StrokeCount) {
+        (self.letter_strokes, self.symbol_strokes)
+    }
+
+    fn count_stroke(&mut self, c: char) {
+        let typed = c.to_string();
+        let (correct, class) = match self.get_current_word_ref().next_expected() {
+            Some(expected) => (expected == typed, StrokeClass::of(expected)),
+            None => (false, StrokeClass::of(&typed)),
+        };
+        let count = match class {
+            StrokeClass::Letter => &mut self.letter_strokes,
+            StrokeClass::Symbol => &mut self.symbol_strokes,
+        };
+        if correct {
+            count.correct += 1;
+        } else {
+            count.incorrect += 1;
+        }
+    }
+
     fn flush(&mut self) {
-        self.rows.remove(0);
-        self.rows.push(self.gen_row());
+        let row = self.rows.remove(0);
+        self.flushed_strokes += row
+            .iter()
+            .map(|word| word.expected().graphemes(true).count())
+            .sum::<usize>();
+        let row = self.gen_row();
+        self.rows.push(row);
         self.current_index = 0;
     }
 
-    pub fn get_parsed(&self) -> Vec<String> {
-        self.rows
-            .iter()
-            .enumerate()
-            .map(|(i, row)| {
-                if i == 0 {
-                    Self::to_string(row, Some(self.current_index))
-                } else {
-                    Self::to_string(row, None)
+    /// Row and column of the character `strokes` keystrokes into the game, not
+    /// counting whitespace between words, if it is on screen.
+    pub fn ghost_position(&self, strokes: usize) -> Option<(usize, usize)> {
+        let mut left = strokes.checked_sub(self.flushed_strokes)?;
+        for (index, row) in self.rows.iter().enumerate() {
+            let mut col = 0;
+            for word in row {
+                let expected = word.expected();
+                let graphemes: Vec<&str> = expected.graphemes(true).collect();
+                if left < graphemes.len() {
+                    let offset: usize = graphemes[..left].iter().map(|g| grapheme_width(g)).sum();
+                    return Some((index, col + offset));
                 }
-            })
-            .collect()
+                left -= graphemes.len();
+                col += word.width() + word.separator().display().width();
+            }
+        }
+        None
     }
 
-    fn to_string(words: &[LiveWord], active_index: Option<u8>) -> String {
-        if let Some(index) = active_index {
-            to_colored_string(words, index)
+    /// Types `c` into the current word, or into the separator after it for
+    /// whitespace, moving on to the next word once it is started.
+    pub fn type_char(&mut self, c: char) {
+        if is_whitespace_key(c) {
+            let word = self.get_current_word_ref();
+            let expects_whitespace = word
+                .next_expected()
+                .is_some_and(|e| e.chars().all(is_whitespace_key));
+            if expects_whitespace && !word.separator().is_started() {
+                self.count_stroke(c);
+                self.get_current_word_ref().push_char(c);
+            } else if !word.actual().is_empty() && word.push_separator(c) {
+                self.move_index();
+            }
         } else {
-            to_string(words)
+            if self.get_current_word_ref().separator().is_started() {
+                self.move_index();
+            }
+            self.count_stroke(c);
+            self.get_current_word_ref().push_char(c);
         }
     }
 
-    pub fn register_key(&mut self, key: Key) {
+    /// Deletes the last character typed into the current word.
+    pub fn delete(&mut self) {
         let word = self.get_current_word_ref();
-        match key {
-            Key::Char(c) => {
-                if c != ' ' {
-                    word.push_char(c);
-                } else {
-                    if word.actual().len() != 0 {
-                        self.move_index();
-                    }
-                }
-            }
-            Key::Backspace => {
-                word.pop_char();
-            }
-            _ => {}
+        if !word.pop_separator() {
+            word.pop_char();
         }
     }
 }
 
-fn gen_row(feed: &WordFeed, fit_row_into_len: u8) -> Vec<LiveWord> {
+fn gen_row(feed: &mut WordFeed, fit_row_into_len: u8) -> Vec<LiveWord> {
     let mut total_lenght = 0;
     let mut words = Vec::new();
 
     loop {
         let word = feed.get_random();
-        let fits = total_lenght + word.expected().len() < fit_row_into_len as usize;
+        let fits = total_lenght + word.width() < fit_row_into_len as usize;
 
         if !fits {
             break;
         };
 
-        total_lenght += word.expected().len() + 1;
+        total_lenght += word.width() + 1;
         words.push(word);
     }
 
     words
 }
 
-fn to_colored_string(words: &[LiveWord], index: u8) -> String {
-    let mut buffer = String::new();
-    let i = index as usize;
-    for (x, word) in words.iter().enumerate() {
-        let string = if x < i {
-            word.to_color_string_outcome()
-        } else if x == i {
-            word.to_color_string_outcome_detail()
-        } else {
-            word.expected()
-        };
-        buffer.push_str(&string);
-        buffer.push(' ');
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
+}
 
-    buffer
+fn is_whitespace_key(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\n'
 }
 
-fn to_string(words: &[LiveWord]) -> String {
-    let mut buffer = String::new();
-    for word in words.iter() {
-        buffer.push_str(&word.expected());
-        buffer.push(' ');
+/// Renders whitespace of a separator so that line breaks stay visible.
+fn display_whitespace(c: char) -> String {
+    match c {
+        '\n' => "↵".to_string(),
+        '\t' => " ".repeat(TAB_WIDTH),
+        c => c.to_string(),
     }
-    buffer
 }
 
-fn get_color_string_outcome_detail(
-    expected: &String,
-    actual: &String,
-    colors: (&str, &str, &str),
-) -> String {
-    let (green, red, reset) = colors;
-    let mut a_chars = actual.chars();
-    let mut buffer = String::new();
-    let mut stop = false;
-
-    buffer.push_str(green);
-
-    for e_char in expected.chars() {
-        if stop {
-            buffer.push(e_char);
-            continue;
-        }
-
-        if let Some(a_char) = a_chars.next() {
-            if e_char == a_char {
-                buffer.push(e_char);
-            } else {
-                buffer.push_str(red);
-                buffer.push(e_char);
-                buffer.push_str(reset);
-                stop = true
-            }
+/// Splits text into words, keeping the exact whitespace between them. Whitespace
+/// that follows the last line break of a run becomes the next word's indentation.
+pub fn tokenize(text: &str) -> Vec<LiveWord> {
+    let mut words = Vec::new();
+    let mut rest = text.trim_start_matches('\n');
+
+    while !rest.is_empty() {
+        let indent_end = rest
+            .find(|c: char| !is_whitespace_key(c))
+            .unwrap_or(rest.len());
+        let word_end = rest[indent_end..]
+            .find(is_whitespace_key)
+            .map_or(rest.len(), |i| indent_end + i);
+        let space_end = rest[word_end..]
+            .find(|c: char| !is_whitespace_key(c))
+            .map_or(rest.len(), |i| word_end + i);
+
+        let space = &rest[word_end..space_end];
+        let separator_end = if space_end == rest.len() {
+            space.len()
         } else {
-            buffer.push_str(reset);
-            buffer.push(e_char);
+            space.rfind('\n').map_or(space.len(), |i| i + 1)
+        };
+        let separator = if separator_end == 0 {
+            "\n"
+        } else {
+            &space[..separator_end]
+        };
+
+        words.push(LiveWord::with_separator(
+            rest[..word_end].to_string(),
+            Separator::new(separator),
+        ));
+        rest = &rest[word_end + separator_end..];
+    }
+
+    words
+}
+
+/// Lays words out into rows, breaking after line breaks and before a row would
+/// grow wider than `fit` columns.
+pub fn split_rows(words: Vec<LiveWord>, fit: usize) -> Vec<Vec<LiveWord>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut width = 0;
+
+    for word in words {
+        let word_width = word.width() + word.separator().display().width();
+        if !row.is_empty() && width + word_width > fit {
+            rows.push(std::mem::take(&mut row));
+            width = 0;
+        }
+        let breaks_line = word.separator().expected().contains('\n');
+        width += word_width;
+        row.push(word);
+        if breaks_line {
+            rows.push(std::mem::take(&mut row));
+            width = 0;
         }
     }
+    if !row.is_empty() {
+        rows.push(row);
+    }
 
-    buffer.push_str(reset);
+    rows
+}
 
-    buffer
+fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme == "\t" {
+        TAB_WIDTH
+    } else {
+        grapheme.width()
+    }
 }
 
-pub fn random(range: Range<u32>) -> u32 {
-    rand::thread_rng().gen_range(range)
+/// Renders a grapheme of the expected word, expanding tabs.
+fn display_grapheme(grapheme: &str) -> String {
+    if grapheme == "\t" {
+        " ".repeat(TAB_WIDTH)
+    } else {
+        grapheme.to_string()
+    }
 }
@@ -0,0 +1,43 @@
+#![allow(dead_code, unused_imports)]
+
+#[cfg(feature = "tui")]
+mod terminal;
+
+#[cfg(feature = "tui")]
+pub use terminal::{Screen, Script};
+
+use ftyper::score::Score;
+use ftyper::words::StrokeCount;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+/// A score of a minute's game at `wpm`.
+pub fn score(wpm: f32) -> Score {
+    Score {
+        correct: 10,
+        incorrect: 1,
+        accuracy: 100.0,
+        wpm,
+        time: 60,
+        letters: StrokeCount::default(),
+        symbols: StrokeCount::default(),
+    }
+}
+
+/// A file named `name` for `test` alone, in a directory removed once done.
+pub struct TempFile(pub PathBuf);
+
+impl TempFile {
+    pub fn new(test: &str, name: &str) -> Self {
+        let dir = env::temp_dir().join(format!("ftyper-test-{}-{}", test, process::id()));
+        Self(dir.join(name))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        fs::remove_dir_all(self.0.parent().unwrap()).ok();
+    }
+}
@@ -0,0 +1,83 @@
+use ftyper::mode::Key;
+use ftyper::terminal::{Input, Output};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Ticks a game may go on for once the script has run out, before it is
+/// considered stuck.
+const MAX_IDLE_TICKS: usize = 100_000;
+
+/// Keys handed to the game one per tick, `None` being a tick without a key.
+pub struct Script {
+    keys: VecDeque<Option<Key>>,
+    idle: usize,
+}
+
+impl Script {
+    pub fn new() -> Self {
+        Self {
+            keys: VecDeque::new(),
+            idle: 0,
+        }
+    }
+
+    pub fn typing(mut self, text: &str) -> Self {
+        self.keys.extend(text.chars().map(|c| Some(Key::Char(c))));
+        self
+    }
+
+    pub fn key(mut self, key: Key) -> Self {
+        self.keys.push_back(Some(key));
+        self
+    }
+
+    pub fn wait(mut self, ticks: usize) -> Self {
+        self.keys.extend((0..ticks).map(|_| None));
+        self
+    }
+}
+
+impl Input for Script {
+    fn next_key(&mut self) -> Option<Key> {
+        match self.keys.pop_front() {
+            Some(key) => key,
+            None => {
+                self.idle += 1;
+                assert!(self.idle < MAX_IDLE_TICKS, "the game never ended");
+                None
+            }
+        }
+    }
+}
+
+/// Everything the game draws, kept for inspection.
+#[derive(Clone, Default)]
+pub struct Screen(Rc<RefCell<Vec<u8>>>);
+
+impl Screen {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).to_string()
+    }
+}
+
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Output for Screen {
+    fn size(&self) -> (u16, u16) {
+        (100, 30)
+    }
+
+    fn set_raw_mode(&mut self, _raw: bool) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{score, TempFile};
+use ftyper::daily::{Day, History};
+use ftyper::score::Score;
+use std::str::FromStr;
+
+fn day(date: &str) -> Day {
+    Day::from_str(date).unwrap()
+}
+
+#[test]
+fn days_are_dates() {
+    for (days, date) in [
+        (0, "1970-01-01"),
+        (11016, "2000-02-29"),
+        (20088, "2024-12-31"),
+        (20744, "2026-10-18"),
+    ] {
+        assert_eq!(Day(days).to_string(), date);
+        assert_eq!(day(date), Day(days));
+    }
+}
+
+#[test]
+fn invalid_dates_are_refused() {
+    for date in [
+        "2026-02-30",
+        "2026-13-01",
+        "2026-10",
+        "yesterday",
+        "1969-12-31",
+    ] {
+        assert!(Day::from_str(date).is_err(), "{}", date);
+    }
+}
+
+#[test]
+fn one_attempt_a_day_is_recorded() {
+    let history = TempFile::new("daily-once", "daily");
+    let history = History::new(&history.0);
+    let today = day("2026-10-18");
+
+    assert!(history.record(today, &score(50.0)).unwrap());
+    assert!(!history.record(today, &score(80.0)).unwrap());
+    assert_eq!(history.attempt(today).unwrap().map(|s| s.wpm), Some(50.0));
+}
+
+#[test]
+fn a_given_up_attempt_is_the_official_one() {
+    let history = TempFile::new("daily-given-up", "daily");
+    let history = History::new(&history.0);
+    let today = day("2026-10-18");
+    let given_up = Score {
+        time: 12,
+        ..score(30.0)
+    };
+
+    assert!(history.record(today, &given_up).unwrap());
+    assert!(!history.record(today, &score(80.0)).unwrap());
+    assert_eq!(history.attempt(today).unwrap().map(|s| s.time), Some(12));
+}
+
+#[test]
+fn streak_counts_the_days_in_a_row() {
+    let history = TempFile::new("daily-streak", "daily");
+    let history = History::new(&history.0);
+    for date in ["2026-10-12", "2026-10-14", "2026-10-15", "2026-10-16"] {
+        history.record(day(date), &score(50.0)).unwrap();
+    }
+
+    // Not played yet today.
+    assert_eq!(history.streak(day("2026-10-17")).unwrap(), 3);
+    history.record(day("2026-10-17"), &score(50.0)).unwrap();
+    assert_eq!(history.streak(day("2026-10-17")).unwrap(), 4);
+    assert_eq!(history.streak(day("2026-10-19")).unwrap(), 0);
+}
@@ -0,0 +1,429 @@
+mod common;
+
+use common::{Screen, Script};
+use ftyper::code::Sources;
+use ftyper::config::Config;
+use ftyper::game::{CommandMode, Game, Task};
+use ftyper::mode::{Key, Mode, OnFinish, SurvivalMode, TimeMode, WatchMode, ZenMode};
+use ftyper::output::Stderr;
+use ftyper::replay::Pace;
+use ftyper::timer::{Clock, FakeClock};
+use ftyper::watch::Watch;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::time::Duration;
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog";
+
+/// Done after being checked `polls` times.
+struct Countdown {
+    polls: usize,
+}
+
+impl Watch for Countdown {
+    fn waiting_for(&self) -> String {
+        "the countdown to end".to_string()
+    }
+
+    fn done(&self) -> String {
+        "the countdown has ended".to_string()
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.polls = self.polls.saturating_sub(1);
+        self.polls == 0
+    }
+}
+
+fn game<M: Mode>(mode: M, script: Script) -> (Game<'static, M>, Screen, FakeClock) {
+    let config = Config {
+        text: Some(TEXT.to_string()),
+        ..Config::default()
+    };
+    let screen = Screen::default();
+    let clock = FakeClock::new();
+    let game = Game::with_terminal(
+        mode,
+        config,
+        Box::new(script),
+        Box::new(screen.clone()),
+        Rc::new(clock.clone()),
+    );
+    (game, screen, clock)
+}
+
+fn countdown(polls: usize, on_finish: OnFinish) -> WatchMode {
+    WatchMode::new(Box::new(Countdown { polls }), on_finish)
+}
+
+/// Runs `args` as main does, with its output captured.
+fn task(name: &str, args: &[&str]) -> Task {
+    let mut command = process::Command::new(args[0]);
+    command
+        .args(&args[1..])
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped());
+    Task {
+        name: name.to_string(),
+        command,
+    }
+}
+
+#[test]
+fn time_mode_ends_at_the_time_limit() {
+    let (mut game, _, clock) = game(TimeMode { time: 2 }, Script::new().typing("the "));
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 2);
+    assert!(clock.now() < Duration::from_secs(3));
+}
+
+#[test]
+fn time_mode_starts_with_the_first_key() {
+    let script = Script::new().wait(40).typing("the ");
+    let (mut game, _, clock) = game(TimeMode { time: 1 }, script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 1);
+    assert!(clock.now() >= Duration::from_secs(3));
+}
+
+#[test]
+fn correct_words_are_scored() {
+    let script = Script::new().typing("the quick brown fox ");
+    let (mut game, _, _) = game(TimeMode { time: 60 }, script);
+    game.start().unwrap();
+
+    let score = game.score();
+    assert_eq!((score.correct, score.incorrect), (4, 0));
+    assert_eq!(score.accuracy, 100.0);
+    // 16 correct keystrokes make 3.2 words of five in a minute.
+    assert!((score.wpm - 3.2).abs() < 0.001);
+}
+
+#[test]
+fn mistyped_words_are_scored_incorrect() {
+    let script = Script::new().typing("teh quick ");
+    let (mut game, _, _) = game(TimeMode { time: 10 }, script);
+    game.start().unwrap();
+
+    let score = game.score();
+    assert_eq!((score.correct, score.incorrect), (1, 1));
+    assert_eq!(score.accuracy, 50.0);
+}
+
+#[test]
+fn backspace_fixes_a_typo() {
+    let script = Script::new().typing("tha").key(Key::Backspace).typing("e ");
+    let (mut game, _, _) = game(TimeMode { time: 10 }, script);
+    game.start().unwrap();
+
+    let score = game.score();
+    assert_eq!((score.correct, score.incorrect), (1, 0));
+    assert_eq!(score.letters.incorrect, 1);
+}
+
+#[test]
+fn quitting_ends_the_game_early() {
+    let script = Script::new().typing("the ").key(Key::Ctrl('c'));
+    let (mut game, _, clock) = game(TimeMode { time: 60 }, script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().correct, 1);
+    assert!(clock.now() < Duration::from_secs(1));
+}
+
+#[test]
+fn score_is_shown_at_the_end() {
+    let script = Script::new().typing("the quick ");
+    let (mut game, screen, _) = game(TimeMode { time: 1 }, script);
+    game.start().unwrap();
+
+    assert!(screen.text().contains("correct: 2  incorrect: 0"));
+}
+
+#[test]
+fn watch_mode_stops_once_done() {
+    let (mut game, screen, clock) = game(countdown(10, OnFinish::Stop), Script::new());
+    game.start().unwrap();
+
+    assert_eq!(clock.now(), Duration::from_millis(500));
+    assert!(screen
+        .text()
+        .contains("The countdown has ended. Here's your score:"));
+}
+
+#[test]
+fn watch_mode_lets_the_word_be_finished() {
+    let script = Script::new().typing("th").wait(20).typing("e ");
+    let (mut game, _, clock) = game(countdown(5, OnFinish::Word), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().correct, 1);
+    assert!(clock.now() > Duration::from_secs(1));
+}
+
+#[test]
+fn watch_mode_continues_for_the_given_time() {
+    let script = Script::new().typing("the ");
+    let (mut game, _, _) = game(countdown(1, OnFinish::Continue(3)), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 3);
+}
+
+#[test]
+fn watch_mode_plays_until_the_minute_is_over() {
+    let script = Script::new().typing("the ");
+    let (mut game, _, _) = game(countdown(30, OnFinish::Minute), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 60);
+}
+
+#[test]
+fn quitting_a_watch_mode_game_before_it_is_done() {
+    let script = Script::new().typing("the").key(Key::Ctrl('c'));
+    let (mut game, screen, _) = game(countdown(1000, OnFinish::Stop), script);
+    game.start().unwrap();
+
+    assert!(!screen.text().contains("Here's your score"));
+}
+
+#[test]
+fn zen_mode_goes_on_until_the_finish_key() {
+    let script = Script::new()
+        .typing("the quick ")
+        .wait(3000)
+        .key(Key::Ctrl('d'));
+    let (mut game, screen, _) = game(ZenMode::new(), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().correct, 2);
+    assert!(game.score().time >= 150);
+    assert!(!screen.text().contains("[ctrl+s: hide]"));
+}
+
+#[test]
+fn zen_mode_shows_stats_on_demand() {
+    let script = Script::new()
+        .typing("the quick ")
+        .key(Key::Ctrl('s'))
+        .wait(20)
+        .key(Key::Esc);
+    let (mut game, screen, _) = game(ZenMode::new(), script);
+    game.start().unwrap();
+
+    assert!(screen
+        .text()
+        .contains("1s  correct: 2  incorrect: 0  accuracy: 100%"));
+}
+
+#[test]
+fn survival_mode_adds_time_for_correct_words() {
+    let script = Script::new().typing("the quick brown ");
+    let (mut game, screen, _) = game(SurvivalMode::new(), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 13);
+    assert!(screen
+        .text()
+        .contains("You survived for 13s and reached level 1."));
+}
+
+#[test]
+fn survival_mode_takes_time_for_mistakes() {
+    let script = Script::new().typing("teh quick ");
+    let (mut game, _, _) = game(SurvivalMode::new(), script);
+    game.start().unwrap();
+
+    assert_eq!(game.score().time, 9);
+}
+
+#[test]
+fn ghost_caret_moves_at_the_given_pace() {
+    let config = Config {
+        text: Some(TEXT.to_string()),
+        pace: Some(Pace::Wpm(60.0)),
+        ..Config::default()
+    };
+    let screen = Screen::default();
+    let mut game = Game::with_terminal(
+        TimeMode { time: 2 },
+        config,
+        Box::new(Script::new().typing("t")),
+        Box::new(screen.clone()),
+        Rc::new(FakeClock::new()),
+    );
+    game.start().unwrap();
+
+    // Five keystrokes a second put the ghost on "q" after one.
+    assert!(screen.text().contains("\x1b[43mq\x1b[49m"));
+}
+
+#[test]
+fn replay_records_correct_keystrokes() {
+    let script = Script::new().typing("tx").key(Key::Backspace).typing("he");
+    let (mut game, _, _) = game(TimeMode { time: 1 }, script);
+    game.start().unwrap();
+
+    assert_eq!(game.replay().strokes, vec![0, 150, 200]);
+}
+
+/// What two games played with `config` and the same seed show.
+fn seeded_screens(config: Config) -> Vec<String> {
+    (0..2)
+        .map(|_| {
+            let config = Config {
+                seed: Some(7),
+                ..config.clone()
+            };
+            let screen = Screen::default();
+            let mut game = Game::with_terminal(
+                TimeMode { time: 1 },
+                config,
+                Box::new(Script::new().key(Key::Ctrl('c'))),
+                Box::new(screen.clone()),
+                Rc::new(FakeClock::new()),
+            );
+            game.start().unwrap();
+            screen.text()
+        })
+        .collect()
+}
+
+#[test]
+fn games_with_the_same_seed_have_the_same_words() {
+    let screens = seeded_screens(Config::default());
+
+    assert_eq!(screens[0], screens[1]);
+}
+
+#[test]
+fn games_with_the_same_seed_have_the_same_code() {
+    let sources = Sources::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src")).unwrap();
+    let screens = seeded_screens(Config {
+        code: Some(sources),
+        ..Config::default()
+    });
+
+    assert_eq!(screens[0], screens[1]);
+}
+
+#[test]
+fn command_mode_ends_with_the_exit_status() {
+    let commands: [(&[&str], i32); 3] = [
+        (&["true"], 0),
+        (&["false"], 1),
+        (&["sh", "-c", "exit 3"], 3),
+    ];
+    for (args, code) in commands {
+        let mode = CommandMode::new(vec![task("task", args)], OnFinish::Stop);
+        let (mut game, _, _) = game(mode, Script::new());
+        let status = game.start().unwrap();
+
+        assert_eq!(status.and_then(|status| status.code()), Some(code));
+    }
+}
+
+#[test]
+fn command_mode_shows_the_output_at_the_end() {
+    let mode = CommandMode::new(
+        vec![task("greet", &["sh", "-c", "echo hello"])],
+        OnFinish::Stop,
+    );
+    let (mut game, screen, _) = game(mode, Script::new());
+    game.start().unwrap();
+
+    assert!(screen.text().contains("hello\n"));
+    assert!(screen
+        .text()
+        .contains("Your process finished successfully. The output is above."));
+}
+
+#[test]
+fn command_mode_shows_stderr_on_its_own_lines() {
+    let mut mode = CommandMode::new(
+        vec![task("task", &["sh", "-c", "printf out; echo err >&2"])],
+        OnFinish::Stop,
+    );
+    mode.set_stderr(Stderr::Separate);
+    let (mut game, screen, _) = game(mode, Script::new());
+    game.start().unwrap();
+
+    assert!(screen.text().contains("out\n--- stderr ---\nerr\n"));
+}
+
+#[test]
+fn command_mode_skips_the_tasks_after_a_failure() {
+    let tasks = vec![task("first", &["false"]), task("second", &["true"])];
+    let (mut game, screen, _) = game(CommandMode::new(tasks, OnFinish::Stop), Script::new());
+    let status = game.start().unwrap();
+
+    assert_eq!(status.and_then(|status| status.code()), Some(1));
+    assert!(screen.text().contains("first failed with exit status 1"));
+    assert!(screen.text().contains("second was skipped"));
+}
+
+#[test]
+fn command_mode_rings_once_the_command_is_done() {
+    // With no word being typed, there is nothing left to finish.
+    let mode = CommandMode::new(vec![task("task", &["true"])], OnFinish::Word);
+    let (mut game, screen, _) = game(mode, Script::new());
+    game.start().unwrap();
+
+    assert!(screen.text().contains('\u{7}'));
+}
+
+#[test]
+fn command_mode_continues_for_the_given_time() {
+    let mode = CommandMode::new(vec![task("task", &["true"])], OnFinish::Continue(3));
+    let (mut game, _, _) = game(mode, Script::new().typing("the "));
+    let status = game.start().unwrap();
+
+    assert!(status.is_some_and(|status| status.success()));
+    assert!(game.score().time >= 3);
+}
+
+#[test]
+fn command_mode_fails_to_start_a_missing_command() {
+    let mode = CommandMode::new(
+        vec![task("missing", &["/nonexistent/command"])],
+        OnFinish::Stop,
+    );
+    let (mut game, _, _) = game(mode, Script::new());
+
+    assert!(game.start().is_err());
+}
+
+#[test]
+fn command_mode_is_timed_by_the_game_clock() {
+    let mode = CommandMode::new(vec![task("sleeper", &["sleep", "5"])], OnFinish::Stop);
+    let script = Script::new().wait(30).key(Key::Ctrl('c'));
+    let (mut game, screen, clock) = game(mode, script);
+    clock.advance(Duration::from_secs(100));
+    game.start().unwrap();
+
+    // 31 ticks make a second and a half on the game clock, whatever time really passed.
+    assert!(screen.text().contains("sleeper running for 1s"));
+    assert!(!screen.text().contains("running for 2s"));
+}
+
+#[test]
+fn quitting_before_the_first_key_leaves_the_game_unstarted() {
+    let (mut game, _, _) = game(TimeMode { time: 60 }, Script::new().key(Key::Ctrl('c')));
+    game.start().unwrap();
+
+    assert!(!game.is_started());
+}
+
+#[test]
+fn the_first_key_starts_the_game() {
+    let script = Script::new().typing("t").key(Key::Ctrl('c'));
+    let (mut game, _, _) = game(TimeMode { time: 60 }, script);
+    game.start().unwrap();
+
+    assert!(game.is_started());
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{score, TempFile};
+use ftyper::config::Config;
+use ftyper::leaderboard::{self, Entry, Leaderboard};
+use ftyper::words::Difficulty;
+use std::fs;
+use std::thread;
+
+fn names(leaderboard: &Leaderboard, mode: &str, n: usize) -> Vec<String> {
+    leaderboard
+        .top(mode, n)
+        .unwrap()
+        .ranked()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[test]
+fn games_are_read_back_as_recorded() {
+    let board = TempFile::new("read-back", "leaderboard");
+    let leaderboard = Leaderboard::new(&board.0);
+    let entry = Entry::new("alice", "time60", score(61.5));
+    leaderboard.record(&entry).unwrap();
+
+    assert_eq!(leaderboard.entries().unwrap(), [entry]);
+}
+
+#[test]
+fn games_recorded_at_once_all_make_it() {
+    let board = TempFile::new("at-once", "leaderboard");
+    let players: Vec<_> = (0..4)
+        .map(|player| {
+            let path = board.0.clone();
+            thread::spawn(move || {
+                let leaderboard = Leaderboard::new(&path);
+                for _ in 0..50 {
+                    let name = format!("player{}", player);
+                    leaderboard
+                        .record(&Entry::new(&name, "time60", score(60.0)))
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for player in players {
+        player.join().unwrap();
+    }
+
+    assert_eq!(Leaderboard::new(&board.0).entries().unwrap().len(), 200);
+}
+
+#[test]
+fn missing_leaderboard_is_empty() {
+    let board = TempFile::new("missing", "leaderboard");
+
+    assert!(Leaderboard::new(&board.0)
+        .top("time60", 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn top_players_are_ranked_by_their_best_game_of_the_mode() {
+    let board = TempFile::new("top", "leaderboard");
+    let leaderboard = Leaderboard::new(&board.0);
+    for (name, mode, wpm) in [
+        ("alice", "time60", 50.0),
+        ("bob", "time60", 55.0),
+        ("alice", "time60", 70.0),
+        ("carol", "time30", 90.0),
+        ("dave", "time60", 40.0),
+    ] {
+        leaderboard
+            .record(&Entry::new(name, mode, score(wpm)))
+            .unwrap();
+    }
+
+    assert_eq!(names(&leaderboard, "time60", 10), ["alice", "bob", "dave"]);
+    assert_eq!(names(&leaderboard, "time60", 2), ["alice", "bob"]);
+    assert_eq!(names(&leaderboard, "time30", 10), ["carol"]);
+}
+
+#[test]
+fn unreadable_lines_are_skipped() {
+    let board = TempFile::new("unreadable", "leaderboard");
+    let leaderboard = Leaderboard::new(&board.0);
+    leaderboard
+        .record(&Entry::new("alice", "time60", score(50.0)))
+        .unwrap();
+    let mut text = fs::read_to_string(&board.0).unwrap();
+    text.push_str("garbage\nbob\ttime60\tyesterday\t1\t2\t3\t4\t5\n");
+    fs::write(&board.0, text).unwrap();
+
+    assert_eq!(names(&leaderboard, "time60", 10), ["alice"]);
+}
+
+#[test]
+fn games_with_word_options_are_ranked_apart() {
+    let mode = |config: Config| leaderboard::mode(30, &config);
+    let difficulty = Difficulty {
+        ranks: 0..200,
+        lengths: 1..=8,
+    };
+
+    assert_eq!(mode(Config::default()).as_deref(), Some("time30"));
+    assert_eq!(
+        mode(Config {
+            difficulty,
+            punctuation: true,
+            ..Config::default()
+        })
+        .as_deref(),
+        Some("time30-top200-max8-punctuation")
+    );
+    assert_eq!(
+        mode(Config {
+            text: Some("the quick brown fox".to_string()),
+            ..Config::default()
+        }),
+        None
+    );
+}
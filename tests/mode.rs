@@ -0,0 +1,108 @@
+use ftyper::config::Config;
+use ftyper::mode::{Key, Mode, OnFinish, Session, SurvivalMode, TimeMode, ZenMode, TICK};
+use ftyper::timer::FakeClock;
+use std::io;
+use std::rc::Rc;
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog";
+
+fn session() -> Session<'static> {
+    let config = Config {
+        text: Some(TEXT.to_string()),
+        ..Config::default()
+    };
+    Session::new(config, Rc::new(FakeClock::new()))
+}
+
+/// Plays a started `mode` without a front end, a key per tick, until it is
+/// finished or the keys run out. Returns whether it is finished.
+fn play<M: Mode>(mode: &mut M, session: &mut Session, keys: &[Key]) -> bool {
+    for &key in keys {
+        session.clock.sleep(TICK);
+        mode.on_tick(session);
+        if mode.is_finished(session) {
+            return true;
+        }
+        if !mode.on_key(session, key) {
+            if let Some(word) = session.type_key(key) {
+                mode.on_word_complete(session, &word);
+            }
+        }
+    }
+    mode.is_finished(session)
+}
+
+fn typing(text: &str) -> Vec<Key> {
+    text.chars().map(Key::Char).collect()
+}
+
+#[test]
+fn time_mode_is_played_without_a_terminal() {
+    let mut session = session();
+    let mut mode = TimeMode { time: 2 };
+    mode.on_start(&mut session).unwrap();
+    let mut keys = typing("the quick ");
+    keys.resize(60, Key::Other);
+
+    assert!(play(&mut mode, &mut session, &keys));
+
+    let score = session.score();
+    assert_eq!((score.correct, score.time), (2, 2));
+}
+
+#[test]
+fn zen_mode_takes_the_finish_key() {
+    let mut session = session();
+    let mut keys = typing("the ");
+    keys.push(Key::Esc);
+    let mut mode = ZenMode::new();
+    mode.on_start(&mut session).unwrap();
+
+    assert!(play(&mut mode, &mut session, &keys));
+    assert_eq!(session.word_queue.words_count(), (1, 0));
+}
+
+#[test]
+fn survival_mode_moves_the_time_limit_with_each_word() {
+    let mut session = session();
+    let mut mode = SurvivalMode::new();
+    mode.on_start(&mut session).unwrap();
+    let start = session.timer.limit();
+
+    // Survival mode picks its own words, so the first one is typed as shown and
+    // the second one mistyped.
+    let word = session.word_queue.current_word().expected().to_string();
+    let keys = typing(&format!("{} x ", word));
+    play(&mut mode, &mut session, &keys);
+
+    assert_eq!(session.word_queue.words_count(), (1, 1));
+    assert_eq!(session.timer.limit(), start + 1 - 2);
+    assert!(session
+        .progress
+        .is_some_and(|progress| progress.status.contains("left")));
+}
+
+#[test]
+fn finishing_asks_for_the_players_attention() {
+    let mut session = session();
+    let words = OnFinish::Word.start(&mut session);
+
+    assert!(session.take_notification());
+    assert!(!session.take_notification());
+    assert!(OnFinish::Word.is_over(&session, words));
+    assert_eq!(
+        OnFinish::Word.hint(&session, "done"),
+        "done, finish your word"
+    );
+}
+
+#[test]
+fn modes_write_their_end_messages_anywhere() {
+    let mut session = session();
+    let mut out = io::Cursor::new(Vec::new());
+    SurvivalMode::new().on_end(&mut session, &mut out, false);
+
+    assert!(String::from_utf8(out.into_inner())
+        .unwrap()
+        .starts_with("You survived for 0s"));
+}
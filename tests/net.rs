@@ -0,0 +1,135 @@
+mod common;
+
+use common::{Screen, Script};
+use ftyper::config::Config;
+use ftyper::game::Game;
+use ftyper::net::{self, Lobby, Message, Peer, Setup};
+use ftyper::race::mode::RaceMode;
+use ftyper::race::Standings;
+use ftyper::replay::Pace;
+use ftyper::timer::Clock;
+use ftyper::words::Difficulty;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog";
+/// How much faster than real time races are played.
+const SPEEDUP: u32 = 10;
+
+/// Real time sped up, so races are quick but players still hear from each other
+/// while they wait for the results.
+struct Fast(Instant);
+
+impl Clock for Fast {
+    fn now(&self) -> Duration {
+        self.0.elapsed() * SPEEDUP
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration / SPEEDUP);
+    }
+}
+
+fn setup() -> Setup {
+    Setup {
+        seed: 42,
+        time: 1,
+        language: "english".to_string(),
+        difficulty: Difficulty::default(),
+        punctuation: false,
+        numbers: true,
+        players: vec!["alice".to_string(), "bob".to_string()],
+    }
+}
+
+fn race(peer: Peer, setup: &Setup, typing: &str) -> Standings {
+    let mut config = Config::default();
+    setup.configure(&mut config).unwrap();
+    config.text = Some(TEXT.to_string());
+    let mut game = Game::with_terminal(
+        RaceMode::new(peer, setup),
+        config,
+        Box::new(Script::new().typing(typing)),
+        Box::new(Screen::default()),
+        Rc::new(Fast(Instant::now())),
+    );
+    game.start().unwrap()
+}
+
+fn names(standings: &Standings) -> Vec<String> {
+    standings
+        .ranked()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[test]
+fn messages_survive_the_trip() {
+    let messages = [
+        Message::Join {
+            name: "bob".to_string(),
+        },
+        Message::Refuse {
+            reason: "bob is already playing".to_string(),
+        },
+        Message::Start(setup()),
+        Message::Progress {
+            name: "bob".to_string(),
+            strokes: 12,
+        },
+    ];
+    for message in messages.iter() {
+        assert_eq!(message.to_string().parse::<Message>().as_ref(), Ok(message));
+    }
+}
+
+#[test]
+fn players_race_over_loopback() {
+    let mut lobby = Lobby::open(0, "alice").unwrap();
+    let address = format!("127.0.0.1:{}", lobby.port().unwrap());
+    let guest = thread::spawn(move || {
+        let (peer, setup) = net::join(&address, "bob").unwrap();
+        assert_eq!(setup.players, ["alice", "bob"]);
+        race(peer, &setup, "the quick brown ")
+    });
+    while lobby.accept().unwrap().is_empty() {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let (peer, setup) = lobby.start(setup());
+    let standings = race(peer, &setup, "the ");
+
+    assert_eq!(names(&standings), ["bob", "alice"]);
+    assert_eq!(names(&guest.join().unwrap()), ["bob", "alice"]);
+}
+
+#[test]
+fn names_must_be_unique() {
+    let mut lobby = Lobby::open(0, "alice").unwrap();
+    let address = format!("127.0.0.1:{}", lobby.port().unwrap());
+    let guest = thread::spawn(move || net::join(&address, "alice").map(|_| ()));
+    while !guest.is_finished() {
+        lobby.accept().unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let err = guest.join().unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "alice is already playing");
+    assert_eq!(lobby.players(), ["alice"]);
+}
+
+#[test]
+fn joining_drops_the_players_own_words_and_pace() {
+    let mut config = Config {
+        text: Some(TEXT.to_string()),
+        pace: Some(Pace::Wpm(80.0)),
+        ..Config::default()
+    };
+    setup().configure(&mut config).unwrap();
+
+    assert!(config.text.is_none());
+    assert!(config.pace.is_none());
+    assert_eq!(config.seed, Some(42));
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use ftyper::race::Standings;
+use ftyper::score::Score;
+
+fn score(wpm: f32, accuracy: f32) -> Score {
+    Score {
+        accuracy,
+        ..common::score(wpm)
+    }
+}
+
+#[test]
+fn fastest_player_ranks_first() {
+    let mut standings = Standings::new();
+    standings.add("alice", score(48.0, 90.0));
+    standings.add("bob", score(61.0, 80.0));
+    standings.add("carol", score(48.0, 95.0));
+
+    let names: Vec<&str> = standings
+        .ranked()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, ["bob", "carol", "alice"]);
+}
+
+#[test]
+fn table_lines_up_the_scores() {
+    let mut standings = Standings::new();
+    standings.add("al", score(48.0, 90.0));
+    standings.add("bartholomew", score(102.4, 100.0));
+
+    let table = standings.table();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "     player       wpm  accuracy  correct  incorrect",
+            " 1.  bartholomew  102   100.00%       10          1",
+            " 2.  al            48    90.00%       10          1",
+        ]
+    );
+}
@@ -0,0 +1,39 @@
+use ftyper::replay::{Pace, Replay};
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+#[test]
+fn replay_is_saved_and_loaded() {
+    let path = env::temp_dir()
+        .join(format!("ftyper-test-{}", std::process::id()))
+        .join("best-time60.replay");
+    let replay = Replay {
+        wpm: 72.5,
+        strokes: vec![0, 120, 250, 400],
+    };
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(loaded.unwrap(), replay);
+}
+
+#[test]
+fn pace_of_a_replay_follows_its_keystrokes() {
+    let pace = Pace::Replay(Replay {
+        wpm: 0.0,
+        strokes: vec![0, 120, 250, 400],
+    });
+
+    assert_eq!(pace.strokes_at(Duration::from_millis(0)), 1);
+    assert_eq!(pace.strokes_at(Duration::from_millis(249)), 2);
+    assert_eq!(pace.strokes_at(Duration::from_secs(1)), 4);
+}
+
+#[test]
+fn pace_in_wpm_counts_five_keystrokes_a_word() {
+    let pace = Pace::Wpm(60.0);
+
+    assert_eq!(pace.strokes_at(Duration::from_secs(2)), 10);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::TempFile;
+use ftyper::code::{CodeFeed, Sources};
+use ftyper::words::{Difficulty, Feed, LiveWord, Typed, WordFeed, WordQueue};
+use std::fs;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const LIST: &str = "a\nan\nthe\nword\nquick\nbrown\njumping\nwonderful\n";
+
+fn lengths(feed: &mut WordFeed) -> Vec<usize> {
+    (0..20)
+        .flat_map(|_| feed.next_row(60))
+        .map(|word| word.expected().graphemes(true).count())
+        .collect()
+}
+
+#[test]
+fn difficulty_can_change_mid_game() {
+    let mut feed = WordFeed::new(LIST, &Difficulty::default());
+    feed.set_difficulty(&Difficulty {
+        ranks: 0..usize::MAX,
+        lengths: 7..=usize::MAX,
+    });
+
+    assert!(lengths(&mut feed).iter().all(|&length| length >= 7));
+}
+
+#[test]
+fn difficulty_matching_no_words_is_ignored() {
+    let easy = Difficulty {
+        ranks: 0..3,
+        lengths: 1..=usize::MAX,
+    };
+    let mut feed = WordFeed::new(LIST, &easy);
+    feed.set_difficulty(&Difficulty {
+        ranks: 0..usize::MAX,
+        lengths: 20..=usize::MAX,
+    });
+
+    assert!(lengths(&mut feed).iter().all(|&length| length <= 3));
+}
+
+#[test]
+fn graphemes_tell_how_they_were_typed() {
+    let states = |typed: &str| {
+        let mut word = LiveWord::new("word".to_string());
+        typed.chars().for_each(|c| {
+            word.push_char(c);
+        });
+        word.graphemes()
+            .into_iter()
+            .map(|(_, typed)| typed)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        states("wo"),
+        [Typed::Correct, Typed::Correct, Typed::Next, Typed::Pending]
+    );
+    assert_eq!(
+        states("wax"),
+        [
+            Typed::Correct,
+            Typed::Incorrect,
+            Typed::Pending,
+            Typed::Pending
+        ]
+    );
+}
+
+#[test]
+fn long_code_lines_are_wrapped_to_the_row() {
+    let file = TempFile::new("long-code-lines", "long.rs");
+    fs::create_dir_all(file.0.parent().unwrap()).unwrap();
+    let items: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+    fs::write(&file.0, format!("let a = [{}];\n", items.join(", "))).unwrap();
+    let mut queue = WordQueue::new(CodeFeed::new(Sources::load(&file.0).unwrap()));
+    queue.init();
+
+    for _ in 0..300 {
+        for row in queue.rows() {
+            let width: usize = row
+                .iter()
+                .map(|word| word.width() + word.separator().display().width())
+                .sum();
+            assert!(width <= 60);
+        }
+        let word = queue.current_word();
+        let typed = format!("{}{}", word.expected(), word.separator().expected());
+        typed.chars().for_each(|c| queue.type_char(c));
+    }
+    assert_eq!(queue.words_count(), (300, 0));
+}
+
+/// Players on different versions share daily challenges and races by seed, so the
+/// words a seed picks must never change.
+#[test]
+fn seeded_words_are_pinned() {
+    let mut feed = WordFeed::new(LIST, &Difficulty::default());
+    feed.set_seed(1);
+    let words: Vec<String> = feed
+        .next_row(60)
+        .iter()
+        .map(|word| word.expected().to_string())
+        .collect();
+
+    assert_eq!(
+        words.join(" "),
+        "word the an the the wonderful an word a a an quick the an"
+    );
+}